@@ -1,9 +1,12 @@
 use anyhow::Result;
-use chrono::Utc;
 use crossterm::event::{KeyCode, KeyEvent};
+use rand::Rng;
+use std::cell::Cell;
+use uuid::Uuid;
 
-use crate::models::Fish;
-use crate::persistence::SaveData;
+use fishtank::models::{registry, species, EquipmentKind, EquipmentModel, Fish, FoodType, IncidentKind, LightSetting, Species, SpeciesRegistry};
+use fishtank::persistence::SaveData;
+use fishtank::simulation::{OfflineReport, SimEvent, SimRng, Simulation};
 
 pub enum AppState {
     Running,
@@ -12,14 +15,29 @@ pub enum AppState {
 
 pub struct App {
     pub state: AppState,
-    pub save_data: SaveData,
-    pub last_update: chrono::DateTime<Utc>,
+    pub sim: Simulation,
     pub animation_frame: u8,
     pub auto_save_timer: f64,
     pub notifications: Vec<String>,
     pub selected_species: usize,  // For cycling through species
-    pub start_time: chrono::DateTime<Utc>,  // For day/night cycle calculation
+    pub selected_food: FoodType,  // What `F` feeds
     pub particles: Vec<Particle>,
+    // Cosmetic draws get their own stream so frame rate never changes the tank
+    pub effects_rng: SimRng,
+    pub away_report: Option<OfflineReport>, // Shown as a modal until dismissed
+    pub away_scroll: Cell<u16>,             // Lines the away report is scrolled down; kept in range when drawn
+    pub family_tree: Option<Uuid>,          // Fish whose family tree is open
+    pub moving_fish: Option<(Uuid, usize)>, // Fish being moved and the tank it's going to
+    pub equipment_menu: Option<usize>,      // Selected row while the equipment menu is open
+    pub light_menu: Option<usize>,          // Selected setting while the light menu is open
+}
+
+/// A line of the equipment menu
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EquipmentRow {
+    Installed(usize),      // Item in the tank on screen
+    Spare(usize),          // Item in the cupboard
+    New(EquipmentModel),   // Fresh out of the box
 }
 
 pub struct Particle {
@@ -27,199 +45,229 @@ pub struct Particle {
     pub y: f32,
     pub speed: f32,
     pub symbol: char,
-    pub lifetime: f32,
 }
 
 impl Particle {
-    pub fn new(x: f32, y: f32, symbol: char) -> Self {
+    pub fn new(x: f32, y: f32, symbol: char, rng: &mut impl Rng) -> Self {
         Self {
             x,
             y,
-            speed: 0.05 + (rand::random::<f32>() * 0.05),
+            speed: 0.05 + (rng.gen::<f32>() * 0.05),
             symbol,
-            lifetime: 1.0, // 0.0 - 1.0 (fade out?) or just time based
         }
     }
 }
 
 impl App {
     pub fn new() -> Result<Self> {
-        let mut save_data = SaveData::load()?;
-        let mut notifications = Vec::new();
-        
-        if !save_data.is_frozen {
-            // Apply offline progression if fish exists
-            let elapsed = save_data.time_since_last_save();
-            let elapsed_seconds = elapsed.num_seconds() as f64;
-            
-            // Cap offline time to prevent excessive decay (24 hours max)
-            let capped_seconds = elapsed_seconds.min(24.0 * 3600.0);
-            
-            // Offline progression is slower (div by 3) to be fair
-            // But game speed is 3x. So accumulating "Game Time" means:
-            // - Online: 1 real sec = 3 game sec
-            // - Offline: 1 real sec = 1 game sec (slower decay)
-            let offline_game_seconds = (capped_seconds * 1.0); 
-
-            if !save_data.fish.is_empty() && capped_seconds > 60.0 {
-                notifications.push(format!(
-                    "⏰ You were away for {}. fish aged naturally.",
-                    Self::format_duration(elapsed_seconds)
-                ));
-                
-                let mut deaths = 0;
-                for fish in &mut save_data.fish {
-                    // For offline updates, assume average water quality or use last known if possible
-                    let default_water = crate::persistence::WaterParams::default(); 
-                    fish.update(offline_game_seconds, &default_water);
-                    if !fish.alive {
-                        deaths += 1;
-                    }
-                }
-                
-                if deaths > 0 {
-                    notifications.push(format!("💀 {} fish passed away during your absence...", deaths));
-                }
-            }
-            
-            // Update total time with offline duration (Game Time)
-            // Advance world clock by 3x real time to maintain day/night cycle continuity
-            save_data.total_time += (elapsed_seconds * 3.0);
+        // Species have to be known before the save's fish are looked up
+        let (species_registry, species_errors) = SpeciesRegistry::load(&SaveData::config_dir().join("species"));
+        species::install_registry(species_registry);
+
+        let save_data = SaveData::load()?;
+        let elapsed_seconds = save_data.time_since_last_save().num_seconds() as f64;
+        let effects_rng = SimRng::new(!save_data.rng.seed()); // Derived from, but distinct from, the tank seed
+        let mut sim = Simulation::new(save_data);
+        let mut notifications: Vec<String> = species_errors
+            .into_iter()
+            .map(|e| format!("⚠️  Bad species file {}", e))
+            .collect();
+
+        let mut away_report = None;
+        if sim.save_data.is_frozen {
+            notifications.push("❄️ Welcome back! World was FROZEN.".to_string());
         } else {
-             notifications.push("❄️ Welcome back! World was FROZEN.".to_string());
+            away_report = sim.apply_offline_progress(elapsed_seconds);
         }
-        
-        // Calculate start time based on total game time
-        let now = Utc::now();
-        let start_time = now - chrono::Duration::seconds(save_data.total_time as i64);
-        
+
         Ok(Self {
             state: AppState::Running,
-            save_data,
-            last_update: now,
+            sim,
             animation_frame: 0,
             auto_save_timer: 0.0,
             notifications,
             selected_species: 0,
-            start_time,
+            selected_food: FoodType::default(),
             particles: Vec::new(),
+            effects_rng,
+            away_report,
+            away_scroll: Cell::new(0),
+            family_tree: None,
+            moving_fish: None,
+            equipment_menu: None,
+            light_menu: None,
         })
     }
 
     pub fn update(&mut self, delta_seconds: f64) {
         // If frozen, just verify auto-save and skip simulation
-        if self.save_data.is_frozen {
-            self.auto_save_timer += delta_seconds;
-            if self.auto_save_timer >= 30.0 {
-                let _ = self.save_data.save();
-                self.auto_save_timer = 0.0;
-            }
+        if self.sim.save_data.is_frozen {
+            self.tick_auto_save(delta_seconds);
             return;
         }
 
-        // Update accumulated time - 3x Speed: 1 Real Sec = 3 Game Sec
-        let game_delta = delta_seconds * 3.0;
-        self.save_data.total_time += game_delta;
-        
-        let is_night = self.is_night();
-
-        // Update water quality
-        let hours = game_delta / 3600.0; // Use game hours for simulation
-        
-        // Purity degrades over time (-1.0 per hour, faster with more fish)
-        let mut degradation_rate = 1.0 + (self.save_data.fish.len() as f32 * 0.5);
-        
-        // Equipment effects
-        if self.save_data.equipment.has_filter {
-            degradation_rate *= 0.5; // Filter reduces dirtying by 50%
-        }
-        if self.save_data.equipment.has_plants {
-             degradation_rate *= 0.9; // Plants help a little (10%)
+        // 3x Speed: 1 Real Sec = 3 Game Sec
+        if let Some(report) = self.sim.run_for(delta_seconds) {
+            self.away_report = Some(report);
+            self.away_scroll.set(0);
         }
 
-        self.save_data.water.purity = (self.save_data.water.purity - (degradation_rate * hours as f32)).max(0.0);
-        
-        // Temperature fluctuations (Warmer day, Cooler night)
-        let target_temp = if is_night { 23.0 } else { 26.0 };
-        let mut temp_diff = target_temp - self.save_data.water.temperature;
-        
-        if self.save_data.equipment.has_heater {
-            temp_diff *= 0.2; // Heater stabilizes temp (80% reduction in fluctuation)
-        }
-        
-        self.save_data.water.temperature += temp_diff * (0.5 * hours as f32);
-
-        // Update all fish
-        let mut new_fry = Vec::new();
-
-        // 1. Basic Update & Movement (Iterate all)
-        for fish in &mut self.save_data.fish {
-             fish.update(game_delta, &self.save_data.water);
-             fish.update_for_time_of_day(is_night);
-             fish.update_position(delta_seconds);
-        }
-
-        // 2. Breeding Pass (Separate to avoid complex borrow issues in one loop)
-        // We need mutable access to pairs.
-        if self.save_data.fish.len() < 10 {
-            let count = self.save_data.fish.len();
-            for i in 0..count {
-                for j in (i+1)..count {
-                    // Use split_at_mut to get two mutable references
-                    let (left, right) = self.save_data.fish.split_at_mut(j);
-                    // left[i] is first fish, right[0] is second fish
-                    
-                    if let Some(fry) = left[i].try_breed(&mut right[0]) {
-                        new_fry.push(fry);
-                    }
+        for event in self.sim.drain_events() {
+            match event {
+                SimEvent::FishBorn { .. } => {
+                    self.add_notification("💕 Love is in the water! A baby is born!");
+                }
+                SimEvent::FishDied { name, cause, .. } => {
+                    let cause = cause.map_or("unknown causes", |c| c.description());
+                    self.add_notification(format!("💀 {} died of {}.", name, cause));
+                }
+                SimEvent::FishFellIll { name, disease, .. } => {
+                    self.add_notification(format!("🦠 {} has {} ({})!", name, disease.name(), disease.symptom()));
+                }
+                SimEvent::FishRecovered { name, disease, .. } => {
+                    self.add_notification(format!("💪 {} recovered from {}.", name, disease.name()));
+                }
+                SimEvent::IncidentStarted { tank, kind } => {
+                    self.add_notification(format!("🚨 {}: {}! Press 'H' to {}.", tank, kind.alert(), kind.response()));
+                }
+                SimEvent::IncidentOver { tank, kind } => {
+                    self.add_notification(format!("{} ({}).", kind.over(), tank));
                 }
             }
         }
-        
-        // Add new fry
-        for mut fry in new_fry {
-             if self.save_data.fish.len() < 10 {
-                 fry.name = format!("Baby {}", self.save_data.fish.len() + 1);
-                 self.save_data.fish.push(fry);
-                 self.add_notification("💕 Love is in the water! A baby is born!".to_string());
-             }
-        }
 
         // Animation frame
         self.animation_frame = (self.animation_frame + 1) % 60;
 
         // --- PARTICLE SYSTEMS ---
-        // Spawn bubbles if filter is on
-        if self.save_data.equipment.has_filter && !self.save_data.is_frozen {
-            if rand::random::<f32>() < 0.2 { // 20% chance per frame
-                 self.particles.push(Particle::new(0.1 + (rand::random::<f32>() * 0.05), 0.9, 'o'));
+        // Spawn bubbles from each filter's outflow, fewer as it clogs and
+        // none at all in a power cut
+        let rng = &mut self.effects_rng;
+        let power_out = self.sim.tank().incidents.iter().any(|i| i.kind() == IncidentKind::PowerOutage);
+        let filters = self
+            .sim
+            .tank()
+            .equipment
+            .items
+            .iter()
+            .filter(|item| item.model.kind() == EquipmentKind::Filter && !power_out);
+        for filter in filters {
+            if rng.gen::<f32>() < 0.2 * filter.efficiency() { // 20% chance per frame when clean
+                 let x = filter.position + (rng.gen::<f32>() * 0.05);
+                 self.particles.push(Particle::new(x, 0.9, 'o', rng));
             }
-            if rand::random::<f32>() < 0.2 { 
-                 self.particles.push(Particle::new(0.85 + (rand::random::<f32>() * 0.05), 0.9, '.'));
+            if rng.gen::<f32>() < 0.2 * filter.efficiency() {
+                 let x = filter.position + (rng.gen::<f32>() * 0.05);
+                 self.particles.push(Particle::new(x, 0.9, '.', rng));
+            }
+        }
+
+        // Air stones send up a thick column of their own from the substrate
+        let stones = self
+            .sim
+            .tank()
+            .equipment
+            .items
+            .iter()
+            .filter(|item| item.model.kind() == EquipmentKind::AirPump && !power_out);
+        for stone in stones {
+            for symbol in ['O', 'o', '.'] {
+                if rng.gen::<f32>() < 0.3 * stone.efficiency() {
+                    let x = stone.position + (rng.gen::<f32>() * 0.02);
+                    self.particles.push(Particle::new(x, 0.95, symbol, rng));
+                }
             }
         }
         
         // Update Particles
-        // delta_seconds is available in scope
         let dt = delta_seconds as f32;
         self.particles.retain_mut(|p| {
             p.y -= p.speed * dt;
             
             // Wobble
-            p.x += (rand::random::<f32>() - 0.5) * 0.01;
+            p.x += (rng.gen::<f32>() - 0.5) * 0.01;
             
             p.y > 0.0 // Keep if below surface
         });
 
-        // Auto-save every 30 seconds
+        self.tick_auto_save(delta_seconds);
+    }
+
+    /// Auto-save every 30 seconds
+    fn tick_auto_save(&mut self, delta_seconds: f64) {
         self.auto_save_timer += delta_seconds;
         if self.auto_save_timer >= 30.0 {
-            let _ = self.save_data.save();
+            let _ = self.sim.save_data.save();
             self.auto_save_timer = 0.0;
         }
     }
 
     pub fn handle_key(&mut self, key: KeyEvent) {
+        // The away report is modal: it swallows keys until dismissed
+        if self.away_report.is_some() {
+            match key.code {
+                KeyCode::Up => self.away_scroll.set(self.away_scroll.get().saturating_sub(1)),
+                KeyCode::Down => self.away_scroll.set(self.away_scroll.get().saturating_add(1)),
+                KeyCode::Enter | KeyCode::Esc | KeyCode::Char(' ') => {
+                    self.away_report = None;
+                    self.away_scroll.set(0);
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        if self.family_tree.is_some() {
+            match key.code {
+                KeyCode::Left => self.cycle_family_tree(-1),
+                KeyCode::Right => self.cycle_family_tree(1),
+                KeyCode::Char('l') | KeyCode::Enter | KeyCode::Esc => self.family_tree = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(selected) = self.equipment_menu {
+            let rows = self.equipment_rows().len();
+            match key.code {
+                KeyCode::Up => self.equipment_menu = Some((selected + rows - 1) % rows),
+                KeyCode::Down => self.equipment_menu = Some((selected + 1) % rows),
+                KeyCode::Left => self.move_selected_equipment(-0.05),
+                KeyCode::Right => self.move_selected_equipment(0.05),
+                KeyCode::Enter => self.use_equipment_row(),
+                KeyCode::Char('c') => self.service_selected_equipment(),
+                KeyCode::Char('x') | KeyCode::Delete => self.discard_selected_spare(),
+                KeyCode::Char('e') | KeyCode::Esc => self.equipment_menu = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(selected) = self.light_menu {
+            let settings = LightSetting::ALL.len();
+            match key.code {
+                KeyCode::Up => self.light_menu = Some((selected + settings - 1) % settings),
+                KeyCode::Down => self.light_menu = Some((selected + 1) % settings),
+                KeyCode::Left => self.adjust_light(LightSetting::ALL[selected], -1),
+                KeyCode::Right => self.adjust_light(LightSetting::ALL[selected], 1),
+                KeyCode::Char('o') | KeyCode::Enter | KeyCode::Esc => self.light_menu = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some((id, to)) = self.moving_fish {
+            match key.code {
+                KeyCode::Left => self.moving_fish = Some((self.fish_after(id, -1), to)),
+                KeyCode::Right => self.moving_fish = Some((self.fish_after(id, 1), to)),
+                KeyCode::Tab | KeyCode::Up | KeyCode::Down => self.cycle_move_target(),
+                KeyCode::Enter => self.finish_move(id, to),
+                KeyCode::Char('v') | KeyCode::Esc => self.moving_fish = None,
+                _ => {}
+            }
+            return;
+        }
+
         match key.code {
             KeyCode::Char('q') | KeyCode::Esc => {
                 self.state = AppState::Quit;
@@ -227,6 +275,9 @@ impl App {
             KeyCode::Char('f') => {
                 self.feed_fish();
             }
+            KeyCode::Char('F') => {
+                self.select_next_food();
+            }
             KeyCode::Char('n') => {
                 self.new_fish();
             }
@@ -240,7 +291,10 @@ impl App {
                 self.clean_tank();
             }
             KeyCode::Char('e') => {
-                self.toggle_equipment();
+                self.equipment_menu = Some(0);
+            }
+            KeyCode::Char('o') => {
+                self.light_menu = Some(0);
             }
             KeyCode::Char('z') => {
                 self.toggle_freeze();
@@ -250,48 +304,63 @@ impl App {
             }
             KeyCode::Char('d') => {
                 // Add a random decoration
-                let types = [crate::models::DecorationType::Rock, crate::models::DecorationType::Plant, crate::models::DecorationType::Castle, crate::models::DecorationType::Skull];
-                
-                // Try to find a non-overlapping spot (Max 10 attempts)
-                let mut placed = false;
-                for _ in 0..10 {
-                    let rand_type = types[rand::random::<usize>() % types.len()];
-                    
-                     // Random X, centered somewhat (0.1 to 0.9)
-                    let x = rand::random::<f32>().clamp(0.1, 0.9);
-                    
-                    // Check overlap with existing decorations
-                    // Heuristic: Assume width ~ 15% (0.15)
-                    let width_allowance = 0.15;
-                    let overlap = self.save_data.decorations.iter().any(|d| (d.position.0 - x).abs() < width_allowance);
-                    
-                    if !overlap {
-                        let deco = crate::models::Decoration::new(rand_type, (x, 0.0));
-                        self.save_data.decorations.push(deco);
-                        self.add_notification(format!("🌿 Added new decoration!"));
-                        placed = true;
-                        break;
-                    }
-                }
-                
-                if !placed {
-                    self.add_notification("❌ Not enough space for decoration!".to_string());
+                if self.sim.add_random_decoration() {
+                    self.add_notification("🌿 Added new decoration!");
+                } else {
+                    self.add_notification("❌ Not enough space for decoration!");
                 }
             }
             KeyCode::Char('s') | KeyCode::Char('S') => {
-                 if self.save_data.algae_level > 0.0 {
-                     self.save_data.algae_level = (self.save_data.algae_level - 20.0).max(0.0);
+                 if self.sim.scrub_algae() {
                      self.add_notification("🧽 Scrubbed the glass!".to_string());
                  } else {
                      self.add_notification("✨ Glass is already sparkling clean!".to_string());
                  }
             }
+            KeyCode::Char('m') => {
+                match self.sim.medicate() {
+                    Some(medication) => self.add_notification(format!(
+                        "💊 Dosed {} (side effect: {})",
+                        medication.name(),
+                        medication.side_effect()
+                    )),
+                    None => self.add_notification("✨ Nobody is sick!"),
+                }
+            }
+            KeyCode::Char('g') => {
+                let substrate = self.sim.cycle_substrate();
+                self.add_notification(format!("🪨 Substrate: {}", substrate.name()));
+            }
+            KeyCode::Char('a') => self.resize_tank(),
+            KeyCode::Char('h') => match self.sim.respond_to_incident() {
+                Some(kind) => self.add_notification(kind.resolved()),
+                None => self.add_notification("✨ Nothing needs dealing with."),
+            },
+            KeyCode::Char('l') => {
+                match self.sim.tank().fish.iter().find(|f| f.alive).or(self.sim.tank().fish.first()) {
+                    Some(fish) => self.family_tree = Some(fish.id),
+                    None => self.add_notification("❌ No fish in tank! Press 'N' to add one."),
+                }
+            }
+            KeyCode::Tab => self.switch_tank(1),
+            KeyCode::BackTab => self.switch_tank(-1),
+            KeyCode::Char('+') => {
+                match self.sim.add_tank() {
+                    Some(name) => {
+                        let name = name.to_string();
+                        self.particles.clear();
+                        self.add_notification(format!("🏠 Set up a new tank: {}", name));
+                    }
+                    None => self.add_notification("❌ No room for another tank!"),
+                }
+            }
+            KeyCode::Char('v') => self.start_move(),
             KeyCode::Char('x') => {
-                self.save_data.decorations.pop(); // Remove last one
+                self.sim.save_data.tank_mut().decorations.pop(); // Remove last one
                 self.add_notification("🗑️ Removed last decoration.".to_string());
             }
             KeyCode::Char('X') => {
-                self.save_data.decorations.clear(); // Remove all
+                self.sim.save_data.tank_mut().decorations.clear(); // Remove all
                 self.add_notification("💥 Cleared all decorations!".to_string());
             }
             _ => {}
@@ -299,49 +368,43 @@ impl App {
     }
 
     fn feed_fish(&mut self) {
-        if self.save_data.fish.is_empty() {
+        if self.sim.tank().fish.is_empty() {
             self.add_notification("❌ No fish in tank! Press 'N' to add one.");
             return;
         }
 
-        let mut fed_count = 0;
-        for fish in &mut self.save_data.fish {
-            if fish.alive {
-                fish.feed();
-                fed_count += 1;
-            }
-        }
+        let fed_count = self.sim.drop_food(self.selected_food);
 
         if fed_count > 0 {
-            self.add_notification(format!("🍽️  Fed {} fish!", fed_count));
+            self.add_notification(format!("🍽️  Dropped {} for {} fish!", self.selected_food.name().to_lowercase(), fed_count));
         } else {
             self.add_notification("💀 All fish have passed away.");
         }
     }
 
-    pub fn new_fish(&mut self) {
-        const MAX_FISH: usize = 10;
-        
-        if self.save_data.fish.len() >= MAX_FISH {
-            self.add_notification(format!("⚠️  Tank full! Maximum {} fish.", MAX_FISH));
-            return;
+    /// Switch what `F` feeds, mentioning which fish in the tank love it
+    fn select_next_food(&mut self) {
+        self.selected_food = self.selected_food.next();
+        let food = self.selected_food;
+
+        let mut fans: Vec<&str> = Vec::new();
+        for fish in self.sim.tank().fish.iter().filter(|f| f.alive) {
+            if fish.species.likes(food) && !fans.contains(&fish.species.name()) {
+                fans.push(fish.species.name());
+            }
+        }
+        if fans.is_empty() {
+            self.add_notification(format!("🥫 Feeding {}.", food.name()));
+        } else {
+            self.add_notification(format!("🥫 Feeding {} (a favourite of {}).", food.name(), fans.join(", ")));
         }
+    }
 
-        // Rotate species (0..7)
-        self.selected_species = (self.selected_species + 1) % 8;
-        
-        // Get species info
-        let (species_name, emoji) = match self.selected_species {
-            0 => ("Goldfish", "🟡"),
-            1 => ("Betta", "🔵"),
-            2 => ("Guppy", "🟢"),
-            3 => ("Neon Tetra", "🔴"),
-            4 => ("Angelfish", "⚪"),
-            5 => ("Clownfish", "🟠"),
-            6 => ("Koi", "🎏"),
-            7 => ("Pufferfish", "🐡"),
-            _ => ("Goldfish", "🟡"),
-        };
+    pub fn new_fish(&mut self) {
+        // Rotate through every registered species
+        let species = registry().all();
+        self.selected_species = (self.selected_species + 1) % species.len();
+        let def = &species[self.selected_species];
         
         // Generate name based on count (or random)
         let fish_names = [
@@ -350,24 +413,121 @@ impl App {
             "Sushi", "Sashimi", "Scale", "Ripple", "Wave",
             "Azure", "Crimson", "Shadow", "Flash", "Spark"
         ];
-        let name_idx = self.save_data.fish.len() % fish_names.len();
+        let name_idx = self.sim.tank().fish.len() % fish_names.len();
         let name = fish_names[name_idx].to_string();
         
-        // Create fish based on selected species
-        let fish = match self.selected_species {
-            0 => Fish::new_goldfish(name.clone()),
-            1 => Fish::new_betta(name.clone()),
-            2 => Fish::new_guppy(name.clone()),
-            3 => Fish::new_neon_tetra(name.clone()),
-            4 => Fish::new_angelfish(name.clone()),
-            5 => Fish::new_clownfish(name.clone()),
-            6 => Fish::new_koi(name.clone()),
-            7 => Fish::new_pufferfish(name.clone()),
-            _ => Fish::new_goldfish(name.clone()),
-        };
+        let species = Species::new(def.id.clone());
+        let clashes = self.sim.incompatible_with(&species);
+        let fish = Fish::new(species, name, &mut self.sim.save_data.rng);
         
-        self.save_data.fish.push(fish);
-        self.add_notification(format!("✨ {} {} added! ({}/{})", emoji, species_name, self.save_data.fish.len(), MAX_FISH));
+        self.sim.add_fish(fish);
+        let stocking = self.sim.stocking() * 100.0;
+        self.add_notification(format!("✨ {} {} added! (stocking {:.0}%)", def.emoji, def.name, stocking));
+        if stocking > 100.0 {
+            self.add_notification("⚠️  Tank is overstocked! Expect the water to foul fast.");
+        }
+        if !clashes.is_empty() {
+            let names: Vec<&str> = clashes.iter().map(|s| s.name()).collect();
+            self.add_notification(format!("⚔️  {} won't get along with: {}", def.name, names.join(", ")));
+        }
+    }
+
+    /// Show the family tree of the fish `step` places along in the tank
+    fn cycle_family_tree(&mut self, step: isize) {
+        let Some(current) = self.family_tree else { return };
+        self.family_tree = Some(self.fish_after(current, step));
+    }
+
+    /// Id of the living fish `step` places along from `current` in the tank
+    /// on screen. Fish no longer alive or in the tank (an ancestor) restart
+    /// from the first.
+    fn fish_after(&self, current: Uuid, step: isize) -> Uuid {
+        let living: Vec<&Fish> = self.sim.tank().fish.iter().filter(|f| f.alive).collect();
+        if living.is_empty() {
+            return current;
+        }
+        let index = living.iter().position(|f| f.id == current).unwrap_or(0) as isize;
+        let next = (index + step).rem_euclid(living.len() as isize) as usize;
+        living[next].id
+    }
+
+    fn switch_tank(&mut self, step: isize) {
+        let name = self.sim.switch_tank(step).to_string();
+        self.particles.clear(); // Bubbles belong to the old tank's filter
+        self.add_notification(format!("🏠 {}", name));
+    }
+
+    /// Move everything into the next size of tank, warning about fish that
+    /// no longer fit
+    fn resize_tank(&mut self) {
+        let size = self.sim.cycle_tank_size();
+        let (length, depth, height) = size.dimensions();
+        self.add_notification(format!(
+            "📐 {} tank: {:.0} L ({:.0}×{:.0}×{:.0} cm), stocking {:.0}%",
+            size.name(),
+            size.litres(),
+            length,
+            depth,
+            height,
+            self.sim.stocking() * 100.0
+        ));
+
+        let mut cramped: Vec<&str> = Vec::new();
+        for fish in self.sim.tank().fish.iter().filter(|f| f.alive) {
+            if fish.species.profile().min_tank_litres > size.litres() && !cramped.contains(&fish.species.name()) {
+                cramped.push(fish.species.name());
+            }
+        }
+        if !cramped.is_empty() {
+            self.add_notification(format!("📏 Too small for: {}", cramped.join(", ")));
+        }
+    }
+
+    /// Pick a fish and a tank to move it to
+    fn start_move(&mut self) {
+        let tanks = self.sim.save_data.tanks.len();
+        if tanks < 2 {
+            self.add_notification("❌ Only one tank! Press '+' to set up another.");
+            return;
+        }
+        let Some(fish) = self.sim.tank().fish.iter().find(|f| f.alive) else {
+            self.add_notification("❌ No fish in tank to move!");
+            return;
+        };
+        let to = (self.sim.save_data.active_tank + 1) % tanks;
+        self.moving_fish = Some((fish.id, to));
+    }
+
+    /// Next tank, other than this one, to move the fish to
+    fn cycle_move_target(&mut self) {
+        let Some((id, to)) = self.moving_fish else { return };
+        let tanks = self.sim.save_data.tanks.len();
+        let mut next = (to + 1) % tanks;
+        if next == self.sim.save_data.active_tank {
+            next = (next + 1) % tanks;
+        }
+        self.moving_fish = Some((id, next));
+    }
+
+    fn finish_move(&mut self, id: Uuid, to: usize) {
+        self.moving_fish = None;
+        let Some(fish) = self.sim.tank().fish.iter().find(|f| f.id == id) else { return };
+        if !fish.alive {
+            let name = fish.name.clone();
+            self.add_notification(format!("💀 {} died before it could be moved.", name));
+            return;
+        }
+        let (name, species) = (fish.name.clone(), fish.species.clone());
+        let clashes = self.sim.incompatible_in(to, &species);
+
+        if self.sim.move_fish(id, to) {
+            let tank = &self.sim.save_data.tanks[to];
+            self.add_notification(format!("🚚 Moved {} to {}.", name, tank.name));
+            if !clashes.is_empty() {
+                let names: Vec<&str> = clashes.iter().map(|s| s.name()).collect();
+                self.add_notification(format!("⚔️  {} won't get along with: {}", name, names.join(", ")));
+            }
+        }
     }
 
     fn clear_notifications(&mut self) {
@@ -375,47 +535,99 @@ impl App {
     }
 
     fn restart_tank(&mut self) {
-        self.save_data.fish.clear();
-        self.save_data.water = SaveData::default().water; // Reset water too
+        self.sim.restart();
         self.notifications.clear();
         self.add_notification("🔄 Tank restarted! Press 'N' to add fish.");
     }
 
     fn clean_tank(&mut self) {
-        if self.save_data.water.purity >= 100.0 {
+        if self.sim.change_water() {
+            self.add_notification("🧼 Water changed! Tank is cleaner.");
+        } else {
             self.add_notification("✨ Water is already crystal clear!");
-            return;
         }
-        
-        self.save_data.water.purity = (self.save_data.water.purity + 30.0).min(100.0);
-        self.save_data.water.ph = 7.0; // Stabilize pH
-        self.add_notification("🧼 Water changed! Tank is cleaner.");
     }
 
-    fn toggle_equipment(&mut self) {
-        let eq = &mut self.save_data.equipment;
-        
-        // Simple cycle: None -> Filter -> Heater -> Plants -> All -> None
-        if !eq.has_filter && !eq.has_heater && !eq.has_plants {
-            eq.has_filter = true;
-            self.add_notification("⚙️ Filter installed!");
-        } else if eq.has_filter && !eq.has_heater {
-            eq.has_heater = true;
-            self.add_notification("🌡️ Heater installed!");
-        } else if eq.has_filter && eq.has_heater && !eq.has_plants {
-             eq.has_plants = true;
-             self.add_notification("🌿 Plants added!");
+    /// Lines of the equipment menu: what's in the tank, then the cupboard,
+    /// then everything that can be installed new
+    pub fn equipment_rows(&self) -> Vec<EquipmentRow> {
+        let installed = (0..self.sim.tank().equipment.items.len()).map(EquipmentRow::Installed);
+        let spares = (0..self.sim.save_data.storage.len()).map(EquipmentRow::Spare);
+        let new = EquipmentModel::ALL.into_iter().map(EquipmentRow::New);
+        installed.chain(spares).chain(new).collect()
+    }
+
+    fn selected_equipment_row(&self) -> Option<EquipmentRow> {
+        self.equipment_rows().get(self.equipment_menu?).copied()
+    }
+
+    /// Install or remove whatever is selected in the equipment menu
+    fn use_equipment_row(&mut self) {
+        match self.selected_equipment_row() {
+            Some(EquipmentRow::Installed(index)) => {
+                let name = self.sim.tank().equipment.items[index].model.name();
+                self.sim.remove_equipment(index);
+                self.add_notification(format!("📦 {} put away in the cupboard.", name));
+            }
+            Some(EquipmentRow::Spare(index)) => {
+                let name = self.sim.save_data.storage[index].model.name();
+                self.sim.install_spare(index);
+                self.add_notification(format!("⚙️ {} installed!", name));
+            }
+            Some(EquipmentRow::New(model)) => {
+                self.sim.install_equipment(model);
+                self.add_notification(format!("⚙️ New {} installed!", model.name().to_lowercase()));
+            }
+            None => {}
+        }
+        // Rows shift around as items move; stay in range
+        let rows = self.equipment_rows().len();
+        self.equipment_menu = self.equipment_menu.map(|selected| selected.min(rows - 1));
+    }
+
+    fn service_selected_equipment(&mut self) {
+        let Some(EquipmentRow::Installed(index)) = self.selected_equipment_row() else {
+            self.add_notification("❌ Only equipment in the tank can be serviced.");
+            return;
+        };
+        let model = self.sim.tank().equipment.items[index].model;
+        if !self.sim.service_equipment(index) {
+            self.add_notification(format!("✨ The {} doesn't need it.", model.name().to_lowercase()));
+        } else if model.kind() == EquipmentKind::Filter {
+            self.add_notification(format!("🧽 Cleaned the {} (rinsed out some bacteria too).", model.name().to_lowercase()));
         } else {
-            eq.has_filter = false;
-            eq.has_heater = false;
-            eq.has_plants = false;
-            self.add_notification("❌ All equipment removed.");
+            self.add_notification(format!("🔧 Serviced the {}.", model.name().to_lowercase()));
+        }
+    }
+
+    fn move_selected_equipment(&mut self, step: f32) {
+        if let Some(EquipmentRow::Installed(index)) = self.selected_equipment_row() {
+            self.sim.move_equipment(index, step);
+        }
+    }
+
+    fn discard_selected_spare(&mut self) {
+        if let Some(EquipmentRow::Spare(index)) = self.selected_equipment_row() {
+            let name = self.sim.save_data.storage[index].model.name();
+            self.sim.discard_spare(index);
+            self.add_notification(format!("🗑️ Threw out the {}.", name.to_lowercase()));
+            let rows = self.equipment_rows().len();
+            self.equipment_menu = self.equipment_menu.map(|selected| selected.min(rows - 1));
+        }
+    }
+
+    fn adjust_light(&mut self, setting: LightSetting, step: i32) {
+        let light = self.sim.adjust_light(setting, step);
+        if light.photoperiod() == 0 {
+            self.add_notification("🌑 On and off at the same hour: the light never comes on!");
+        } else if light.photoperiod() > 14 && setting != LightSetting::Intensity {
+            self.add_notification(format!("🟢 {} h of light a day will feed algae.", light.photoperiod()));
         }
     }
 
     fn toggle_freeze(&mut self) {
-        self.save_data.is_frozen = !self.save_data.is_frozen;
-        if self.save_data.is_frozen {
+        self.sim.save_data.is_frozen = !self.sim.save_data.is_frozen;
+        if self.sim.save_data.is_frozen {
             self.add_notification("❄️  World FROZEN! (Weekend Mode)");
         } else {
             self.add_notification("▶️  World UNPAUSED!");
@@ -424,41 +636,23 @@ impl App {
 
     fn toggle_theme(&mut self) {
         let themes = crate::ui::theme::ThemeManager::get_themes();
-        self.save_data.theme_index = (self.save_data.theme_index + 1) % themes.len();
-        self.add_notification(format!("🎨 Theme: {}", themes[self.save_data.theme_index].name));
+        self.sim.save_data.theme_index = (self.sim.save_data.theme_index + 1) % themes.len();
+        self.add_notification(format!("🎨 Theme: {}", themes[self.sim.save_data.theme_index].name));
     }
 
     pub fn get_current_theme(&self) -> crate::ui::theme::Theme {
         let themes = crate::ui::theme::ThemeManager::get_themes();
-        themes.get(self.save_data.theme_index).cloned().unwrap_or_default()
+        themes.get(self.sim.save_data.theme_index).cloned().unwrap_or_default()
     }
 
-    /// Get current game time (accelerated 2x - 12 hour real = 24 hour game)
     /// Get current game time (accelerated 3x - 8 hour real = 24 hour game)
     pub fn get_game_time(&self) -> (u8, u8) {
-        // We use the start_time delta, which effectively tracks total_time
-        // properties: start_time = now - total_time
-        // so: now - start_time = total_time
-        // total_time is ALREADY accumulated at 3x speed in update()
-        // So we just take the raw elapsed seconds as the Game Time.
-        
-        let elapsed = Utc::now().signed_duration_since(self.start_time);
-        let game_seconds = elapsed.num_seconds();
-        
-        let game_time = game_seconds % (24 * 3600);  // 24-hour cycle
-        
-        // Wrap gracefully if time is negative (shouldn't happen but safe)
-        let game_time = if game_time < 0 { 0 } else { game_time };
-        
-        let hour = (game_time / 3600) as u8;
-        let minute = ((game_time % 3600) / 60) as u8;
-        (hour, minute)
+        self.sim.game_time()
     }
 
     /// Check if it's currently night time
     pub fn is_night(&self) -> bool {
-        let (hour, _) = self.get_game_time();
-        hour < 6 || hour >= 18
+        self.sim.is_night()
     }
 
     pub fn add_notification(&mut self, msg: impl Into<String>) {
@@ -469,7 +663,7 @@ impl App {
         }
     }
 
-    fn format_duration(seconds: f64) -> String {
+    pub fn format_duration(seconds: f64) -> String {
         let hours = (seconds / 3600.0) as i64;
         let minutes = ((seconds % 3600.0) / 60.0) as i64;
         
@@ -484,7 +678,7 @@ impl App {
     }
 
     pub fn save_and_quit(&mut self) -> Result<()> {
-        self.save_data.save()?;
+        self.sim.save_data.save()?;
         Ok(())
     }
 }
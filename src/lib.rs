@@ -0,0 +1,11 @@
+//! Fishtank simulation core.
+//!
+//! Everything needed to run a tank without a terminal: the fish and decoration
+//! models, save data persistence and the [`Simulation`] that steps them. The
+//! TUI binary is one front end over this crate.
+
+pub mod models;
+pub mod persistence;
+pub mod simulation;
+
+pub use simulation::{OfflineReport, SimEvent, SimRng, Simulation};
@@ -1,11 +1,8 @@
 mod app;
-mod models;
-mod persistence;
 mod ui;
 mod utils;
 
 use anyhow::Result;
-use chrono::{DateTime, Utc};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event},
     execute,
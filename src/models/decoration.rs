@@ -1,3 +1,4 @@
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -17,9 +18,9 @@ pub enum DecorationType {
 }
 
 impl Decoration {
-    pub fn new(deco_type: DecorationType, position: (f32, f32)) -> Self {
+    pub fn new(deco_type: DecorationType, position: (f32, f32), rng: &mut impl Rng) -> Self {
         Self {
-            id: Uuid::new_v4(),
+            id: super::random_id(rng),
             deco_type,
             position,
         }
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+/// Illnesses a fish can catch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Disease {
+    Ich,         // White spot parasite; stress and temperature trouble, very contagious
+    FinRot,      // Bacterial; dirty water, spreads slowly
+    SwimBladder, // Overfeeding and cold water; not contagious
+}
+
+impl Disease {
+    pub const ALL: [Disease; 3] = [Disease::Ich, Disease::FinRot, Disease::SwimBladder];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Disease::Ich => "Ich",
+            Disease::FinRot => "Fin rot",
+            Disease::SwimBladder => "Swim bladder",
+        }
+    }
+
+    /// What you can see on an infected fish
+    pub fn symptom(&self) -> &'static str {
+        match self {
+            Disease::Ich => "white spots",
+            Disease::FinRot => "ragged fins",
+            Disease::SwimBladder => "can't hold its depth",
+        }
+    }
+
+    /// Chance per game hour, at full severity, to pass on to each tank mate
+    pub fn contagion(&self) -> f32 {
+        match self {
+            Disease::Ich => 0.05,
+            Disease::FinRot => 0.02,
+            Disease::SwimBladder => 0.0,
+        }
+    }
+
+    /// Severity gained per game hour by a badly stressed, untreated fish
+    pub fn progression(&self) -> f32 {
+        match self {
+            Disease::Ich => 2.0,
+            Disease::FinRot => 1.5,
+            Disease::SwimBladder => 1.0,
+        }
+    }
+
+    /// Health lost per game hour at full severity
+    pub fn harm(&self) -> f32 {
+        match self {
+            Disease::Ich => 4.0,
+            Disease::FinRot => 3.0,
+            Disease::SwimBladder => 2.0,
+        }
+    }
+
+    /// The medication that treats it
+    pub fn cure(&self) -> Medication {
+        match self {
+            Disease::Ich => Medication::IchTreatment,
+            Disease::FinRot => Medication::Antibiotic,
+            Disease::SwimBladder => Medication::EpsomSalt,
+        }
+    }
+}
+
+/// A disease a fish has caught and how far along it is
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Infection {
+    pub disease: Disease,
+    pub severity: f32, // 0.0 - 100.0, cured at 0
+}
+
+impl Infection {
+    pub fn new(disease: Disease) -> Self {
+        Self { disease, severity: 10.0 }
+    }
+}
+
+/// Medications that can be dosed into the tank
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Medication {
+    IchTreatment, // Stresses fish and sets back the bacteria colony a little
+    Antibiotic,   // Wipes out nitrifying bacteria: expect an ammonia spike
+    EpsomSalt,    // Hardens the water and pushes pH up
+}
+
+impl Medication {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Medication::IchTreatment => "Ich treatment",
+            Medication::Antibiotic => "Antibiotics",
+            Medication::EpsomSalt => "Epsom salt",
+        }
+    }
+
+    /// Game hours a full course lasts
+    pub fn course_hours(&self) -> f32 {
+        match self {
+            Medication::IchTreatment => 48.0,
+            Medication::Antibiotic => 72.0,
+            Medication::EpsomSalt => 24.0,
+        }
+    }
+
+    pub fn side_effect(&self) -> &'static str {
+        match self {
+            Medication::IchTreatment => "stresses fish, harms bacteria",
+            Medication::Antibiotic => "kills the bacteria colony",
+            Medication::EpsomSalt => "raises pH",
+        }
+    }
+
+    pub fn treats(&self, disease: Disease) -> bool {
+        disease.cure() == *self
+    }
+}
+
+/// A course of medication running in the tank
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Treatment {
+    pub medication: Medication,
+    pub hours_left: f32, // Game hours until the dose is used up
+}
+
+impl Treatment {
+    pub fn new(medication: Medication) -> Self {
+        Self { medication, hours_left: medication.course_hours() }
+    }
+}
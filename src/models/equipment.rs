@@ -0,0 +1,255 @@
+use serde::{Deserialize, Serialize};
+
+/// Share of its effect a completely worn item loses
+const WORN_LOSS: f32 = 0.9;
+
+/// Wear (0 - 100) past which an item is due for maintenance
+const SERVICE_DUE: f32 = 50.0;
+
+/// What a piece of equipment does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EquipmentKind {
+    Filter,
+    Heater,
+    Plants,
+    Lid,
+    AirPump,
+}
+
+impl EquipmentKind {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            EquipmentKind::Filter => "⚡",
+            EquipmentKind::Heater => "🌡️",
+            EquipmentKind::Plants => "🌿",
+            EquipmentKind::Lid => "🔲",
+            EquipmentKind::AirPump => "🫧",
+        }
+    }
+
+    /// ASCII art drawn in the tank. Filters hang from the rim, heaters are
+    /// clipped to the glass, and plants and air stones sit on the substrate.
+    /// A lid spans the whole top and is drawn separately.
+    pub fn sprite(&self) -> &'static [&'static str] {
+        match self {
+            EquipmentKind::Filter => &["▛▀▜", " ║ ", " ║ ", " ║ "],
+            EquipmentKind::Heater => &["╥", "┃", "┃", "┃", "┃"],
+            EquipmentKind::Plants => &[" ) ( ", "( Y )", " \\|/ "],
+            EquipmentKind::Lid => &[],
+            EquipmentKind::AirPump => &["▄█▄"],
+        }
+    }
+
+    /// Needs mains power to run
+    pub fn powered(&self) -> bool {
+        matches!(self, EquipmentKind::Filter | EquipmentKind::Heater | EquipmentKind::AirPump)
+    }
+}
+
+/// Make and model of a piece of equipment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EquipmentModel {
+    SpongeFilter,   // Cheap and gentle, clogs quickly
+    HangOnFilter,   // Hangs on the back of the tank, the all-rounder
+    CanisterFilter, // Lots of media, slow to clog
+    Heater50W,
+    Heater100W,
+    LivePlants, // Not a machine, but they clean the water all the same
+    Lid,        // Keeps jumpers in
+    AirStone,   // Air pump and stone, bubbling oxygen into the water
+}
+
+impl EquipmentModel {
+    pub const ALL: [EquipmentModel; 8] = [
+        EquipmentModel::SpongeFilter,
+        EquipmentModel::HangOnFilter,
+        EquipmentModel::CanisterFilter,
+        EquipmentModel::Heater50W,
+        EquipmentModel::Heater100W,
+        EquipmentModel::LivePlants,
+        EquipmentModel::Lid,
+        EquipmentModel::AirStone,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EquipmentModel::SpongeFilter => "Sponge filter",
+            EquipmentModel::HangOnFilter => "Hang-on filter",
+            EquipmentModel::CanisterFilter => "Canister filter",
+            EquipmentModel::Heater50W => "50 W heater",
+            EquipmentModel::Heater100W => "100 W heater",
+            EquipmentModel::LivePlants => "Live plants",
+            EquipmentModel::Lid => "Lid",
+            EquipmentModel::AirStone => "Air stone",
+        }
+    }
+
+    pub fn kind(&self) -> EquipmentKind {
+        match self {
+            EquipmentModel::SpongeFilter | EquipmentModel::HangOnFilter | EquipmentModel::CanisterFilter => {
+                EquipmentKind::Filter
+            }
+            EquipmentModel::Heater50W | EquipmentModel::Heater100W => EquipmentKind::Heater,
+            EquipmentModel::LivePlants => EquipmentKind::Plants,
+            EquipmentModel::Lid => EquipmentKind::Lid,
+            EquipmentModel::AirStone => EquipmentKind::AirPump,
+        }
+    }
+
+    /// Share of the usual purity loss it takes out when clean
+    pub fn filtration(&self) -> f32 {
+        match self {
+            EquipmentModel::SpongeFilter => 0.3,
+            EquipmentModel::HangOnFilter => 0.5,
+            EquipmentModel::CanisterFilter => 0.7,
+            EquipmentModel::LivePlants => 0.1,
+            _ => 0.0,
+        }
+    }
+
+    /// Extra stocking capacity, as a share of what the bare tank holds
+    pub fn capacity_bonus(&self) -> f32 {
+        match self {
+            EquipmentModel::SpongeFilter => 0.5,
+            EquipmentModel::HangOnFilter => 1.0,
+            EquipmentModel::CanisterFilter => 1.5,
+            EquipmentModel::LivePlants => 0.1,
+            _ => 0.0,
+        }
+    }
+
+    /// Strongest nitrifying colony its media can host (0 - 100)
+    pub fn colony_cap(&self) -> f32 {
+        match self {
+            EquipmentModel::SpongeFilter => 80.0,
+            EquipmentModel::HangOnFilter | EquipmentModel::CanisterFilter => 100.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Heating power; a tank wants about a watt per litre
+    pub fn watts(&self) -> f32 {
+        match self {
+            EquipmentModel::Heater50W => 50.0,
+            EquipmentModel::Heater100W => 100.0,
+            _ => 0.0,
+        }
+    }
+
+    /// How much it stirs up the surface so oxygen gets in (1.0 = an air stone)
+    pub fn aeration(&self) -> f32 {
+        match self {
+            EquipmentModel::SpongeFilter => 0.6, // Driven by bubbles of its own
+            EquipmentModel::HangOnFilter => 0.5, // Splashes back in over the lip
+            EquipmentModel::CanisterFilter => 0.4, // Spray bar rippling the surface
+            EquipmentModel::AirStone => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Wear gained per game hour in an averagely stocked tank
+    pub fn wear_rate(&self) -> f32 {
+        match self {
+            EquipmentModel::SpongeFilter => 1.5,
+            EquipmentModel::HangOnFilter => 0.8,
+            EquipmentModel::CanisterFilter => 0.4,
+            EquipmentModel::Heater50W | EquipmentModel::Heater100W => 0.05,
+            EquipmentModel::AirStone => 0.1, // The stone slowly clogs up
+            EquipmentModel::LivePlants | EquipmentModel::Lid => 0.0,
+        }
+    }
+
+    /// Where it goes when first installed, across the tank (0.0 - 1.0)
+    pub fn default_position(&self) -> f32 {
+        match self.kind() {
+            EquipmentKind::Filter => 0.85,
+            EquipmentKind::Heater => 0.05,
+            EquipmentKind::Plants => 0.3,
+            EquipmentKind::Lid => 0.5,
+            EquipmentKind::AirPump => 0.6,
+        }
+    }
+}
+
+/// One piece of equipment, in a tank or in the cupboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquipmentItem {
+    pub model: EquipmentModel,
+    pub position: f32, // Across the tank, 0.0 - 1.0
+    #[serde(default)]
+    pub wear: f32,     // 0.0 = new, 100.0 = clogged solid or burnt out
+}
+
+impl EquipmentItem {
+    pub fn new(model: EquipmentModel) -> Self {
+        Self {
+            model,
+            position: model.default_position(),
+            wear: 0.0,
+        }
+    }
+
+    /// Share (0.0 - 1.0) of its rated effect it still delivers
+    pub fn efficiency(&self) -> f32 {
+        1.0 - self.wear / 100.0 * WORN_LOSS
+    }
+
+    pub fn needs_service(&self) -> bool {
+        self.wear >= SERVICE_DUE
+    }
+}
+
+/// Everything installed in a tank
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Equipment {
+    pub items: Vec<EquipmentItem>,
+}
+
+impl Equipment {
+    pub fn has(&self, kind: EquipmentKind) -> bool {
+        self.items.iter().any(|item| item.model.kind() == kind)
+    }
+
+    /// Share (0.0 - 1.0) of the usual purity loss taken out, allowing for wear
+    pub fn filtration(&self) -> f32 {
+        let passed: f32 = self
+            .items
+            .iter()
+            .map(|item| 1.0 - item.model.filtration() * item.efficiency())
+            .product();
+        1.0 - passed
+    }
+
+    /// Multiplier on the stocking capacity of the bare tank. Goes by what is
+    /// installed, not how clean it is, so the stocking meter holds still.
+    pub fn capacity_multiplier(&self) -> f32 {
+        1.0 + self.items.iter().map(|item| item.model.capacity_bonus()).sum::<f32>()
+    }
+
+    /// Strongest colony the filters can host, or `None` without a filter
+    pub fn colony_cap(&self) -> Option<f32> {
+        self.items
+            .iter()
+            .filter(|item| item.model.kind() == EquipmentKind::Filter)
+            .map(|item| item.model.colony_cap())
+            .max_by(f32::total_cmp)
+    }
+
+    /// Surface agitation from everything installed, allowing for wear
+    pub fn aeration(&self) -> f32 {
+        self.items.iter().map(|item| item.model.aeration() * item.efficiency()).sum()
+    }
+
+    /// Just what keeps working without power
+    pub fn unpowered(&self) -> Equipment {
+        Equipment {
+            items: self.items.iter().filter(|item| !item.model.kind().powered()).cloned().collect(),
+        }
+    }
+
+    /// How well (0.0 - 1.0) the heaters can hold the temperature of `litres`
+    pub fn heating(&self, litres: f32) -> f32 {
+        let watts: f32 = self.items.iter().map(|item| item.model.watts() * item.efficiency()).sum();
+        (watts / litres.max(1.0)).min(1.0)
+    }
+}
@@ -1,7 +1,14 @@
-use chrono::{DateTime, Duration, Utc};
+use chrono::Duration;
+use std::collections::{BTreeMap, HashMap};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::disease::{Disease, Infection};
+use super::food::FoodType;
+use super::genetics::Genome;
+use super::species::Species;
+
 /// Represents a single fish in the aquarium
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Fish {
@@ -19,58 +26,135 @@ pub struct Fish {
     pub age: Duration,    // Time since birth
     #[serde(default = "default_growth_stage")]
     pub stage: GrowthStage,
-    #[serde(default = "default_gender")]
-    pub gender: Gender,
+    pub gender: Gender, // Filled in for old saves by `SaveData::migrate`
+    #[serde(default)]
+    pub genome: Genome,
+    #[serde(default)]
+    pub parents: Option<(Uuid, Uuid)>, // (mother, father); None for shop-bought fish
+    #[serde(default)]
+    pub generation: u32, // 0 for shop-bought fish, one more than the older parent otherwise
+    #[serde(default)]
+    pub born_at: f64,    // Game clock (total_time) when the fish entered the tank
     pub position: (f32, f32),  // Tank coordinates (0.0-1.0)
+    #[serde(skip)]
+    pub prev_position: Option<(f32, f32)>, // Position before the last step (for smooth rendering)
     pub velocity: (f32, f32),  // Movement direction
     pub state: FishState,
     pub alive: bool,
-    
-    pub created_at: DateTime<Utc>,
-    pub last_fed: Option<DateTime<Utc>>,
     #[serde(default)]
-    pub mate_cooldown: i64, // Seconds until next breeding attempt
+    pub mate_cooldown: f64, // Game seconds until next breeding attempt
+    #[serde(default)]
+    pub cause_of_death: Option<DeathCause>,
+    #[serde(default)]
+    pub infection: Option<Infection>,
+    #[serde(default)]
+    pub lifespan_days: Option<f32>, // Drawn around the species' lifespan at birth; None for old saves
+    #[serde(default)]
+    pub diet: BTreeMap<FoodType, f32>, // Hunger points of each food eaten lately, fading over time; ordered so sums are reproducible
+    #[serde(skip)]
+    pub gasping: bool, // Short of oxygen and hanging at the surface
 }
 
 fn default_growth_stage() -> GrowthStage {
     GrowthStage::Juvenile // Default for existing saves
 }
 
-fn default_gender() -> Gender {
-    if rand::random() { Gender::Male } else { Gender::Female }
-}
-
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Gender {
     Male,
     Female,
 }
 
+/// Tank conditions a fish reacts to besides the water itself
+#[derive(Debug, Clone)]
+pub struct Surroundings {
+    pub algae_level: f32, // 0.0 - 100.0
+    pub tank_litres: f32,
+    pub population: HashMap<Species, usize>, // Living fish of each species
+}
+
+impl Surroundings {
+    /// Living fish of `species` in the tank
+    pub fn living(&self, species: &Species) -> usize {
+        self.population.get(species).copied().unwrap_or(0)
+    }
+}
+
+/// What killed a fish
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
-pub enum Species {
-    Goldfish,
-    Betta,
-    Guppy,
-    NeonTetra,
-    Angelfish,
-    Clownfish,
-    Koi,
-    Pufferfish,
+pub enum DeathCause {
+    Starvation,
+    Unhappiness,
+    DirtyWater,
+    Temperature,
+    Ammonia,
+    Nitrite,
+    Nitrate,
+    Ph,
+    OldAge,
+    Illness(Disease),
+    Injuries,
+    Malnutrition,
+    JumpedOut,
+    Suffocation,
 }
 
-impl Species {
-    pub fn name(&self) -> &'static str {
+impl DeathCause {
+    pub fn description(&self) -> &'static str {
         match self {
-            Species::Goldfish => "Goldfish",
-            Species::Betta => "Betta",
-            Species::Guppy => "Guppy",
-            Species::NeonTetra => "Neon Tetra",
-            Species::Angelfish => "Angelfish",
-            Species::Clownfish => "Clownfish",
-            Species::Koi => "Koi",
-            Species::Pufferfish => "Pufferfish",
+            DeathCause::Starvation => "starvation",
+            DeathCause::Unhappiness => "unhappiness",
+            DeathCause::DirtyWater => "dirty water",
+            DeathCause::Temperature => "bad temperature",
+            DeathCause::Ammonia => "ammonia poisoning",
+            DeathCause::Nitrite => "nitrite poisoning",
+            DeathCause::Nitrate => "nitrate build-up",
+            DeathCause::Ph => "pH shock",
+            DeathCause::OldAge => "old age",
+            DeathCause::Illness(Disease::Ich) => "ich",
+            DeathCause::Illness(Disease::FinRot) => "fin rot",
+            DeathCause::Illness(Disease::SwimBladder) => "swim bladder disease",
+            DeathCause::Injuries => "injuries from a tank mate",
+            DeathCause::Malnutrition => "a monotonous diet",
+            DeathCause::JumpedOut => "jumping out of the tank",
+            DeathCause::Suffocation => "lack of oxygen",
         }
     }
+
+    /// Cause with the largest total share of `harm`
+    fn worst(harm: &[(DeathCause, f32)]) -> Option<DeathCause> {
+        let total = |cause: DeathCause| -> f32 {
+            harm.iter().filter(|(c, _)| *c == cause).map(|(_, amount)| amount).sum()
+        };
+        harm.iter()
+            .map(|(cause, _)| *cause)
+            .max_by(|a, b| total(*a).total_cmp(&total(*b)))
+    }
+}
+
+/// What a species needs from its tank
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct SpeciesProfile {
+    pub temperature: (f32, f32), // Comfortable range, Celsius
+    pub ph: (f32, f32),          // Comfortable range
+    pub activity: f32,           // 1.0 = average; scales swim speed and energy use
+    pub hunger_rate: f32,        // 1.0 = average
+    pub lifespan_days: f32,      // Expected lifespan in game days
+    pub min_tank_litres: f32,    // Smallest tank it's happy in
+    #[serde(default = "default_adult_cm")]
+    pub adult_cm: f32,           // Length when fully grown
+    #[serde(default = "default_waste")]
+    pub waste: f32,              // 1.0 = average waste output
+    #[serde(default)]
+    pub min_school: usize,       // Smallest group it feels safe in; 0 = doesn't school
+}
+
+fn default_adult_cm() -> f32 {
+    5.0
+}
+
+fn default_waste() -> f32 {
+    1.0
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -82,191 +166,46 @@ pub enum FishState {
 }
 
 impl Fish {
-    fn random_gender() -> Gender {
-        if rand::random() { Gender::Male } else { Gender::Female }
-    }
-
-    fn random_position() -> (f32, f32) {
-        (rand::random::<f32>().clamp(0.1, 0.9), rand::random::<f32>().clamp(0.1, 0.9))
+    fn random_gender(rng: &mut impl Rng) -> Gender {
+        if rng.gen() { Gender::Male } else { Gender::Female }
     }
 
-    pub fn new_goldfish(name: String) -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            species: Species::Goldfish,
-            name,
-            hunger: 80.0,
-            happiness: 75.0,
-            health: 100.0,
-            energy: 100.0,
-            age: Duration::zero(),
-            stage: GrowthStage::Fry,
-            gender: Self::random_gender(),
-            position: Self::random_position(),
-            velocity: (0.01, 0.0),
-            state: FishState::Swimming,
-            alive: true,
-            created_at: Utc::now(),
-            last_fed: None,
-            mate_cooldown: 0,
-        }
-    }
-
-    pub fn new_betta(name: String) -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            species: Species::Betta,
-            name,
-            hunger: 85.0,
-            happiness: 70.0,
-            health: 100.0,
-            energy: 90.0,
-            age: Duration::zero(),
-            stage: GrowthStage::Fry,
-            gender: Self::random_gender(),
-            position: Self::random_position(),
-            velocity: (0.008, 0.0),
-            state: FishState::Swimming,
-            alive: true,
-            created_at: Utc::now(),
-            last_fed: None,
-            mate_cooldown: 0,
-        }
-    }
-
-    pub fn new_guppy(name: String) -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            species: Species::Guppy,
-            name,
-            hunger: 70.0,
-            happiness: 85.0,
-            health: 100.0,
-            energy: 100.0,
-            age: Duration::zero(),
-            stage: GrowthStage::Fry,
-            gender: Self::random_gender(),
-            position: Self::random_position(),
-            velocity: (0.015, 0.0),
-            state: FishState::Swimming,
-            alive: true,
-            created_at: Utc::now(),
-            last_fed: None,
-            mate_cooldown: 0,
-        }
-    }
-
-    pub fn new_neon_tetra(name: String) -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            species: Species::NeonTetra,
-            name,
-            hunger: 75.0,
-            happiness: 80.0,
-            health: 100.0,
-            energy: 95.0,
-            age: Duration::zero(),
-            stage: GrowthStage::Fry,
-            gender: Self::random_gender(),
-            position: Self::random_position(),
-            velocity: (0.012, 0.0),
-            state: FishState::Swimming,
-            alive: true,
-            created_at: Utc::now(),
-            last_fed: None,
-            mate_cooldown: 0,
-        }
-    }
-
-    pub fn new_angelfish(name: String) -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            species: Species::Angelfish,
-            name,
-            hunger: 80.0,
-            happiness: 75.0,
-            health: 100.0,
-            energy: 85.0,
-            age: Duration::zero(),
-            stage: GrowthStage::Fry,
-            gender: Self::random_gender(),
-            position: Self::random_position(),
-            velocity: (0.007, 0.0),
-            state: FishState::Swimming,
-            alive: true,
-            created_at: Utc::now(),
-            last_fed: None,
-            mate_cooldown: 0,
-        }
-    }
-    
-    pub fn new_clownfish(name: String) -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            species: Species::Clownfish,
-            name,
-            hunger: 75.0,
-            happiness: 90.0,
-            health: 100.0,
-            energy: 95.0,
-            age: Duration::zero(),
-            stage: GrowthStage::Fry,
-            gender: Self::random_gender(),
-            position: Self::random_position(),
-            velocity: (0.01, 0.0),
-            state: FishState::Swimming,
-            alive: true,
-            created_at: Utc::now(),
-            last_fed: None,
-            mate_cooldown: 0,
-        }
-    }
-
-    pub fn new_koi(name: String) -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            species: Species::Koi,
-            name,
-            hunger: 90.0, // Big fish
-            happiness: 80.0,
-            health: 100.0,
-            energy: 80.0,
-            age: Duration::zero(),
-            stage: GrowthStage::Fry,
-            gender: Self::random_gender(),
-            position: Self::random_position(),
-            velocity: (0.005, 0.0), // Slow and majestic
-            state: FishState::Swimming,
-            alive: true,
-            created_at: Utc::now(),
-            last_fed: None,
-            mate_cooldown: 0,
-        }
+    fn random_position(rng: &mut impl Rng) -> (f32, f32) {
+        (rng.gen::<f32>().clamp(0.1, 0.9), rng.gen::<f32>().clamp(0.1, 0.9))
     }
 
-    pub fn new_pufferfish(name: String) -> Self {
+    /// New fry of `species`, starting with the stats from its definition
+    pub fn new(species: Species, name: String, rng: &mut impl Rng) -> Self {
+        let def = species.def();
+        let stats = def.stats;
         Self {
-            id: Uuid::new_v4(),
-            species: Species::Pufferfish,
+            id: super::random_id(rng),
+            species,
             name,
-            hunger: 70.0, 
-            happiness: 70.0,
+            hunger: stats.hunger,
+            happiness: stats.happiness,
             health: 100.0,
-            energy: 90.0,
+            energy: stats.energy,
             age: Duration::zero(),
             stage: GrowthStage::Fry,
-            gender: Self::random_gender(),
-            position: Self::random_position(),
-            velocity: (0.008, 0.0),
+            gender: Self::random_gender(rng),
+            genome: Genome::random(rng),
+            parents: None,
+            generation: 0,
+            born_at: 0.0, // Stamped by the simulation when the fish is added
+            position: Self::random_position(rng),
+            prev_position: None,
+            velocity: (stats.speed, 0.0),
             state: FishState::Swimming,
             alive: true,
-            created_at: Utc::now(),
-            last_fed: None,
-            mate_cooldown: 0,
+            mate_cooldown: 0.0,
+            cause_of_death: None,
+            infection: None,
+            lifespan_days: Some(def.needs.lifespan_days * rng.gen_range(0.8..1.2)),
+            diet: BTreeMap::new(),
+            gasping: false,
         }
     }
-
-
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -274,13 +213,37 @@ pub enum GrowthStage {
     Fry,      // 0-12 hours
     Juvenile, // 12-36 hours
     Adult,    // > 36 hours
+    Elder,    // Last quarter of the fish's lifespan
+}
+
+impl GrowthStage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GrowthStage::Fry => "Fry",
+            GrowthStage::Juvenile => "Juvenile",
+            GrowthStage::Adult => "Adult",
+            GrowthStage::Elder => "Elder",
+        }
+    }
 }
 
+/// Share of its lifespan after which a fish counts as an elder
+const ELDER_AGE: f32 = 0.75;
+
+/// Game hours over which a fish's memory of its meals fades (by ~63%)
+const DIET_MEMORY_HOURS: f32 = 72.0;
+
+/// A diet is monotonous once one food makes up this much of what was eaten
+const MONOTONY_SHARE: f32 = 0.85;
+
+/// Dissolved oxygen (mg/L) below which fish struggle to breathe
+const LOW_OXYGEN: f32 = 4.0;
+
 impl Fish {
     // Methods
 
     /// Update fish stats based on elapsed time
-    pub fn update(&mut self, delta_seconds: f64, water: &crate::persistence::WaterParams) {
+    pub fn update(&mut self, delta_seconds: f64, water: &crate::persistence::WaterParams, surroundings: &Surroundings) {
         if !self.alive {
             self.state = FishState::Dead;
             return;
@@ -288,19 +251,23 @@ impl Fish {
 
         // Update age (Game Time)
         // delta_seconds passed here MUST be game time (3x real time)
-        self.age = self.age + Duration::seconds(delta_seconds as i64);
+        self.age += Duration::microseconds((delta_seconds * 1_000_000.0) as i64);
 
         // Update Growth Stage
         // Fry: < 12 hours
         // Juvenile: 12 - 36 hours
         // Adult: > 36 hours
+        // Elder: last quarter of its lifespan
         let age_hours = self.age.num_hours();
+        let life_used = self.life_used();
         self.stage = if age_hours < 12 {
             GrowthStage::Fry
         } else if age_hours < 36 {
             GrowthStage::Juvenile
-        } else {
+        } else if life_used < ELDER_AGE {
             GrowthStage::Adult
+        } else {
+            GrowthStage::Elder
         };
 
         // Stat degradation rates (per real hour)
@@ -315,64 +282,148 @@ impl Fish {
         // Base hunger rate (adjusted for 12h real time survival = 36h game time)
         let base_rate = 3.0; 
         
+        let profile = self.species.profile();
+        
         // Species modulation
-        let species_mod = match self.species {
-            Species::Guppy => 1.2,       // Faster
-            Species::Betta => 0.8,       // Slower
-            _ => 1.0,
-        };
+        let species_mod = profile.hunger_rate;
         
         // Stage modulation (Fry eat faster/more often relative to size, but let's keep simple)
         let stage_mod = match self.stage {
             GrowthStage::Fry => 1.5,
             GrowthStage::Juvenile => 1.2,
             GrowthStage::Adult => 1.0,
+            GrowthStage::Elder => 0.8,
         };
 
-        let hunger_rate = base_rate * species_mod * stage_mod;
+        // Bigger fish need more food
+        let hunger_rate = base_rate * species_mod * stage_mod * self.genome.size.value();
         self.hunger = (self.hunger - (hunger_rate * hours as f32)).max(0.0);
+
+        // Meals are slowly forgotten, so a varied diet has to be kept up
+        let fade = (-(hours as f32) / DIET_MEMORY_HOURS).exp();
+        for eaten in self.diet.values_mut() {
+            *eaten *= fade;
+        }
         
         // Happiness decreases
         self.happiness = (self.happiness - (1.5 * hours as f32)).max(0.0);
         
+        // Distance outside the species' comfortable temperature
+        let (temp_min, temp_max) = profile.temperature;
+        let temp_off = (temp_min - water.temperature).max(water.temperature - temp_max).max(0.0);
+
         // Energy decreases during day, regenerates during rest
+        // Active species and fish fighting the wrong temperature tire faster
         if matches!(self.state, FishState::Resting) {
             self.energy = (self.energy + (10.0 * hours as f32)).min(100.0); // Faster sleep recovery
         } else {
-            self.energy = (self.energy - (2.0 * hours as f32)).max(0.0);
+            let temperament = 0.8 + 0.4 * self.genome.temperament.value(); // Restless fish burn more
+            let drain = 2.0 * profile.activity * temperament + 0.5 * temp_off;
+            self.energy = (self.energy - (drain * hours as f32)).max(0.0);
         }
 
         // Health penalties from Environment
+        // Each penalty is tagged with its cause so a fatal update can say why
         let mut health_change = 0.0;
+        let mut harm: Vec<(DeathCause, f32)> = Vec::new();
         
         // Water Purity Impact
         if water.purity < 50.0 {
-            health_change -= 2.0; // Dirty water hurts health
+            harm.push((DeathCause::DirtyWater, 2.0)); // Dirty water hurts health
             self.happiness -= 2.0 * hours as f32; // And happiness
         }
         if water.purity < 20.0 {
-            health_change -= 5.0; // Very dirty water is dangerous
+            harm.push((DeathCause::DirtyWater, 5.0)); // Very dirty water is dangerous
         }
 
-        // Temperature Impact (Idea: 24-26 is good)
-        if water.temperature < 20.0 || water.temperature > 30.0 {
-             health_change -= 1.0;
+        // Temperature Impact, worse the further outside the species' range
+        if temp_off > 0.0 {
+             harm.push((DeathCause::Temperature, temp_off * 0.5));
+             self.happiness -= temp_off * hours as f32;
+        }
+
+        // Nitrogen compounds: ammonia and nitrite burn gills, nitrate is a slow stressor
+        if water.ammonia > 0.25 {
+            harm.push((DeathCause::Ammonia, (water.ammonia - 0.25) * 3.0));
+            self.happiness -= 1.0 * hours as f32;
+        }
+        if water.nitrite > 0.5 {
+            harm.push((DeathCause::Nitrite, (water.nitrite - 0.5) * 2.0));
+        }
+        if water.nitrate > 40.0 {
+            harm.push((DeathCause::Nitrate, (water.nitrate - 40.0) * 0.05));
+        }
+        // Short of oxygen, fish hang at the surface gulping air
+        self.gasping = water.oxygen < LOW_OXYGEN;
+        if self.gasping {
+            harm.push((DeathCause::Suffocation, (LOW_OXYGEN - water.oxygen) * 2.0));
+            self.happiness -= 2.0 * hours as f32;
+        }
+        let toxic = water.ammonia > 0.25 || water.nitrite > 0.5;
+
+        // A little algae is grazing, a glass wall of it is gloomy
+        if surroundings.algae_level > 50.0 {
+            self.happiness -= (surroundings.algae_level - 50.0) / 50.0 * 2.0 * hours as f32;
+        }
+
+        // Cramped fish are unhappy, however clean the water
+        if surroundings.tank_litres < profile.min_tank_litres {
+            let cramped = (profile.min_tank_litres / surroundings.tank_litres.max(1.0) - 1.0).min(3.0);
+            self.happiness -= cramped * 2.0 * hours as f32;
+        }
+
+        // Schooling fish kept without enough of their own kind are nervous wrecks
+        let school = surroundings.living(&self.species);
+        if school < profile.min_school {
+            let missing = (profile.min_school - school) as f32 / profile.min_school as f32;
+            self.happiness -= missing * 3.0 * hours as f32;
+        }
+
+        // pH outside what the fish tolerates stresses it, worse the further out
+        let (ph_min, ph_max) = self.ph_tolerance();
+        let ph_off = (ph_min - water.ph).max(water.ph - ph_max).max(0.0);
+        if ph_off > 0.0 {
+            harm.push((DeathCause::Ph, ph_off * 2.0));
+            self.happiness -= ph_off * 3.0 * hours as f32;
+        }
+
+        // Past its expected lifespan a fish wears out, faster the longer it holds on
+        if life_used > 1.0 {
+            harm.push((DeathCause::OldAge, 1.0 + (life_used - 1.0) * 20.0));
+        }
+
+        // Illness wears a fish down in proportion to how far along it is
+        if let Some(infection) = self.infection {
+            let severity = infection.severity / 100.0;
+            harm.push((DeathCause::Illness(infection.disease), severity * infection.disease.harm()));
+            self.happiness -= severity * 2.0 * hours as f32;
+        }
+
+        // Nothing but the same food for days wears a fish down
+        let monotonous = self.monotonous_diet();
+        if monotonous {
+            harm.push((DeathCause::Malnutrition, 0.3));
         }
 
         // Health is affected by hunger and happiness
         if self.hunger < 20.0 || self.happiness < 20.0 {
-            health_change -= 3.0; // Starvation hurts more now
-        } else if self.hunger > 50.0 && self.happiness > 50.0 && water.purity > 80.0 {
+            // Starvation hurts more now
+            let cause = if self.hunger < 20.0 { DeathCause::Starvation } else { DeathCause::Unhappiness };
+            harm.push((cause, 3.0));
+        } else if self.hunger > 50.0 && self.happiness > 50.0 && water.purity > 80.0 && !toxic && !self.gasping && ph_off == 0.0 && temp_off == 0.0 && !monotonous {
             // Slowly regenerate health when well cared for AND clean water
             health_change += 0.5;
         }
-        
+
+        health_change -= harm.iter().map(|(_, amount)| amount).sum::<f32>() / self.genome.hardiness.value();
         self.health = (self.health + (health_change * hours as f32)).clamp(0.0, 100.0);
+        self.happiness = self.happiness.clamp(0.0, 100.0);
 
         // Death check
         if self.health <= 0.0 {
             self.alive = false;
             self.state = FishState::Dead;
+            self.cause_of_death = DeathCause::worst(&harm);
         }
 
         // Auto-transition to resting if energy is low
@@ -382,13 +433,13 @@ impl Fish {
         }
         
         // Cooldown decay
-        if self.mate_cooldown > 0 {
-            self.mate_cooldown -= delta_seconds as i64;
+        if self.mate_cooldown > 0.0 {
+            self.mate_cooldown = (self.mate_cooldown - delta_seconds).max(0.0);
         }
     }
     
     /// Attempt to breed with another fish
-    pub fn try_breed(&mut self, partner: &mut Fish) -> Option<Fish> {
+    pub fn try_breed(&mut self, partner: &mut Fish, rng: &mut impl Rng) -> Option<Fish> {
         // Validation
         if !self.alive || !partner.alive { return None; }
         if self.species != partner.species { return None; }
@@ -396,7 +447,7 @@ impl Fish {
         if self.stage != GrowthStage::Adult || partner.stage != GrowthStage::Adult { return None; }
         
         // Check cooldowns
-        if self.mate_cooldown > 0 || partner.mate_cooldown > 0 { return None; }
+        if self.mate_cooldown > 0.0 || partner.mate_cooldown > 0.0 { return None; }
         
         // Check stats (Must be healthy and happy)
         if self.health < 80.0 || partner.health < 80.0 { return None; }
@@ -405,22 +456,17 @@ impl Fish {
         // Success!
         // Reset cooldowns (e.g. 5 minutes = 300 game seconds)
         // 5 real minutes = 900 game seconds
-        let cooldown = 900;
+        let cooldown = 900.0;
         self.mate_cooldown = cooldown;
         partner.mate_cooldown = cooldown;
         
         // Spawn Fry
         // Name will be placeholder, parent logic in App will name it
-        let mut fry = match self.species {
-            Species::Goldfish => Fish::new_goldfish("Baby".to_string()),
-            Species::Betta => Fish::new_betta("Baby".to_string()),
-            Species::Guppy => Fish::new_guppy("Baby".to_string()),
-            Species::NeonTetra => Fish::new_neon_tetra("Baby".to_string()),
-            Species::Angelfish => Fish::new_angelfish("Baby".to_string()),
-            Species::Clownfish => Fish::new_clownfish("Baby".to_string()),
-            Species::Koi => Fish::new_koi("Baby".to_string()),
-            Species::Pufferfish => Fish::new_pufferfish("Baby".to_string()),
-        };
+        let mut fry = Fish::new(self.species.clone(), "Baby".to_string(), rng);
+        let (mother, father) = if self.gender == Gender::Female { (&*self, &*partner) } else { (&*partner, &*self) };
+        fry.genome = Genome::inherit(&mother.genome, &father.genome, rng);
+        fry.parents = Some((mother.id, father.id));
+        fry.generation = mother.generation.max(father.generation) + 1;
         
         // Inherit some position
         fry.position = self.position;
@@ -428,35 +474,94 @@ impl Fish {
         Some(fry)
     }
 
-    /// Update fish state based on time of day (call from App::update)
-    pub fn update_for_time_of_day(&mut self, is_night: bool) {
+    /// Update fish state based on the light in the tank (call from App::update)
+    pub fn update_for_time_of_day(&mut self, is_dark: bool) {
         if !self.alive {
             return;
         }
 
-        if is_night && !matches!(self.state, FishState::Resting | FishState::Dead) {
-            // Fish rest at night
+        if is_dark && !matches!(self.state, FishState::Resting | FishState::Dead) {
+            // Fish rest with the lights out
             self.state = FishState::Resting;
-        } else if !is_night && matches!(self.state, FishState::Resting) && self.energy > 40.0 {
-            // Wake up during day if energy is sufficient
+        } else if !is_dark && matches!(self.state, FishState::Resting) && self.energy > 40.0 {
+            // Wake up in the light if energy is sufficient
             self.state = FishState::Swimming;
         }
     }
 
-    /// Feed the fish
-    pub fn feed(&mut self) {
+    /// Take a bite of up to `amount` hunger points of `food`.
+    /// Returns how much the fish actually ate.
+    pub fn eat(&mut self, amount: f32, food: FoodType) -> f32 {
         if !self.alive {
-            return;
+            return 0.0;
         }
 
-        self.hunger = (self.hunger + 30.0).min(100.0);
-        self.happiness = (self.happiness + 10.0).min(100.0);
-        self.last_fed = Some(Utc::now());
+        let eaten = amount.min(100.0 - self.hunger).max(0.0);
+        self.hunger += eaten;
+        *self.diet.entry(food).or_insert(0.0) += eaten;
+
+        // Favourite foods are a treat
+        let treat = if self.species.likes(food) { 2.0 } else { 1.0 };
+        self.happiness = (self.happiness + eaten / 3.0 * treat).min(100.0);
         self.state = FishState::Eating;
+        eaten
+    }
+
+    /// Whether the fish has been eating almost nothing but one food lately
+    pub fn monotonous_diet(&self) -> bool {
+        let total: f32 = self.diet.values().sum();
+        let most = self.diet.values().copied().fold(0.0, f32::max);
+        total > 50.0 && most / total > MONOTONY_SHARE
+    }
+
+    /// Take `amount` health of injury, dying of it at zero
+    pub fn injure(&mut self, amount: f32) {
+        if !self.alive {
+            return;
+        }
+        self.health = (self.health - amount).max(0.0);
+        if self.health <= 0.0 {
+            self.alive = false;
+            self.state = FishState::Dead;
+            self.cause_of_death = Some(DeathCause::Injuries);
+        }
+    }
+
+    /// How stressed the fish is, 0.0 (content) - 1.0 (miserable)
+    pub fn stress(&self) -> f32 {
+        ((100.0 - self.happiness) + (100.0 - self.health)) / 200.0
+    }
+
+    /// pH range (min, max) this fish is comfortable in
+    pub fn ph_tolerance(&self) -> (f32, f32) {
+        self.species.profile().ph
+    }
+
+    /// Ammonia this fish excretes, in ppm per game hour
+    pub fn waste_rate(&self) -> f32 {
+        if !self.alive {
+            return 0.0;
+        }
+        let size = match self.stage {
+            GrowthStage::Fry => 0.3,
+            GrowthStage::Juvenile => 0.6,
+            GrowthStage::Adult | GrowthStage::Elder => 1.0,
+        };
+        0.02 * size * self.genome.size.value() * self.species.profile().waste
+    }
+
+    /// Share of a tank's capacity the fish takes up, in centimetres of
+    /// average-waste adult fish. Fry are counted at the size they'll grow to.
+    pub fn bioload(&self) -> f32 {
+        if !self.alive {
+            return 0.0;
+        }
+        let profile = self.species.profile();
+        profile.adult_cm * self.genome.size.value() * profile.waste
     }
 
     /// Get warning status
-    pub fn get_warnings(&self) -> Vec<String> {
+    pub fn get_warnings(&self, water: &crate::persistence::WaterParams, surroundings: &Surroundings) -> Vec<String> {
         let mut warnings = Vec::new();
 
         if !self.alive {
@@ -476,16 +581,57 @@ impl Fish {
         if self.energy < 30.0 {
             warnings.push("😴 Exhausted".to_string());
         }
+        if let Some(infection) = self.infection {
+            warnings.push(format!("🦠 {}: {}", infection.disease.name(), infection.disease.symptom()));
+        }
+        if self.life_used() > 1.0 {
+            warnings.push("🕯️  Living on borrowed time".to_string());
+        }
+        if self.monotonous_diet() {
+            warnings.push("🥣 Bored of the same food".to_string());
+        }
+
+        let profile = self.species.profile();
+        if water.temperature < profile.temperature.0 {
+            warnings.push("🥶 Too cold".to_string());
+        } else if water.temperature > profile.temperature.1 {
+            warnings.push("🥵 Too warm".to_string());
+        }
+        if water.ph < profile.ph.0 {
+            warnings.push("🧪 Water too acidic".to_string());
+        } else if water.ph > profile.ph.1 {
+            warnings.push("🧪 Water too alkaline".to_string());
+        }
+        if self.gasping {
+            warnings.push("🫧 Gasping for air".to_string());
+        }
+        if surroundings.tank_litres < profile.min_tank_litres {
+            warnings.push("📏 Tank too small".to_string());
+        }
+        let bullies: Vec<&str> = surroundings
+            .population
+            .keys()
+            .filter(|other| other.harasses(&self.species) && (**other != self.species || surroundings.living(other) > 1))
+            .map(|other| other.name())
+            .collect();
+        if !bullies.is_empty() {
+            warnings.push(format!("⚔️  Bullied by {}", bullies.join(", ")));
+        }
+        if surroundings.living(&self.species) < profile.min_school {
+            warnings.push(format!("🐟 Lonely, wants a school of {}+", profile.min_school));
+        }
 
         warnings
     }
 
     /// Simple movement AI
-    pub fn update_position(&mut self, delta_seconds: f64) {
+    pub fn update_position(&mut self, delta_seconds: f64, rng: &mut impl Rng) {
         if !self.alive || matches!(self.state, FishState::Dead) {
             return;
         }
 
+        self.prev_position = Some(self.position);
+
         // Speed Factor
         let speed = if matches!(self.state, FishState::Eating) {
             0.5
@@ -494,12 +640,23 @@ impl Fish {
         } else {
             1.0
         };
+        let age = if self.stage == GrowthStage::Elder { 0.6 } else { 1.0 }; // Old fish take it slow
 
-        let dt = delta_seconds as f32 * speed;
+        let dt = delta_seconds as f32 * speed * age * self.species.profile().activity * self.genome.speed_factor();
         
         self.position.0 += self.velocity.0 * dt;
         self.position.1 += self.velocity.1 * dt;
 
+        // A bad swim bladder floats the fish up whatever it tries
+        if let Some(Infection { disease: Disease::SwimBladder, severity }) = self.infection {
+            self.position.1 -= severity / 100.0 * 0.02 * delta_seconds as f32;
+        }
+
+        // Gasping fish make for the surface whatever they were doing
+        if self.gasping {
+            self.position.1 -= 0.05 * delta_seconds as f32;
+        }
+
         // Bounce off boundaries (keep within 0.1 - 0.9 range)
         if self.position.0 <= 0.1 || self.position.0 >= 0.9 {
             self.velocity.0 *= -1.0;
@@ -510,15 +667,32 @@ impl Fish {
             self.position.1 = self.position.1.clamp(0.1, 0.9);
         }
 
-        // Occasionally change direction
-        if rand::random::<f32>() < 0.01 {
+        // Occasionally change direction; schooling fish mostly follow the school
+        let wander = if self.species.profile().min_school > 0 { 0.001 } else { 0.01 };
+        if rng.gen::<f32>() < wander {
             self.velocity = (
-                (rand::random::<f32>() - 0.5) * 0.02,
-                (rand::random::<f32>() - 0.5) * 0.02,
+                (rng.gen::<f32>() - 0.5) * 0.02,
+                (rng.gen::<f32>() - 0.5) * 0.02,
             );
         }
     }
 
+    /// Position to draw at, `alpha` (0.0-1.0) of the way from the previous
+    /// step to the current one
+    pub fn render_position(&self, alpha: f32) -> (f32, f32) {
+        super::lerp_position(self.prev_position, self.position, alpha)
+    }
+
+    /// Expected lifespan in game days
+    pub fn expected_lifespan_days(&self) -> f32 {
+        self.lifespan_days.unwrap_or_else(|| self.species.profile().lifespan_days)
+    }
+
+    /// Fraction of its expected lifespan the fish has lived (1.0 = due)
+    pub fn life_used(&self) -> f32 {
+        (self.age.num_seconds() as f32 / 86_400.0) / self.expected_lifespan_days().max(0.1)
+    }
+
     pub fn age_display(&self) -> String {
         let days = self.age.num_days();
         let hours = self.age.num_hours() % 24;
@@ -0,0 +1,124 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Depth at which sinking food comes to rest on the substrate
+pub const SUBSTRATE_DEPTH: f32 = 0.92;
+
+/// Kinds of food that can be fed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+pub enum FoodType {
+    #[default]
+    Flakes,      // Everyday staple, floats a while
+    Pellets,     // Dense and filling, drops quickly
+    Bloodworms,  // Rich treat for carnivores, very messy when left
+    AlgaeWafers, // Sinks straight to the bottom for grazers
+    BrineShrimp, // Light live food, drifts slowly
+}
+
+impl FoodType {
+    pub const ALL: [FoodType; 5] = [
+        FoodType::Flakes,
+        FoodType::Pellets,
+        FoodType::Bloodworms,
+        FoodType::AlgaeWafers,
+        FoodType::BrineShrimp,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FoodType::Flakes => "Flakes",
+            FoodType::Pellets => "Pellets",
+            FoodType::Bloodworms => "Bloodworms",
+            FoodType::AlgaeWafers => "Algae wafers",
+            FoodType::BrineShrimp => "Brine shrimp",
+        }
+    }
+
+    /// Hunger points in one piece
+    pub fn nutrition(&self) -> f32 {
+        match self {
+            FoodType::Flakes => 8.0,
+            FoodType::Pellets => 12.0,
+            FoodType::Bloodworms => 15.0,
+            FoodType::AlgaeWafers => 10.0,
+            FoodType::BrineShrimp => 10.0,
+        }
+    }
+
+    /// Sinking speed relative to flakes
+    pub fn sink_speed(&self) -> f32 {
+        match self {
+            FoodType::Flakes => 1.0,
+            FoodType::Pellets => 2.0,
+            FoodType::Bloodworms => 1.5,
+            FoodType::AlgaeWafers => 3.0,
+            FoodType::BrineShrimp => 0.6,
+        }
+    }
+
+    /// Waste released per point of food that rots, relative to flakes
+    pub fn pollution(&self) -> f32 {
+        match self {
+            FoodType::Flakes => 1.0,
+            FoodType::Pellets => 0.8,
+            FoodType::Bloodworms => 2.0,
+            FoodType::AlgaeWafers => 1.2,
+            FoodType::BrineShrimp => 1.5,
+        }
+    }
+
+    /// How a piece looks: (sinking, settled) glyphs and an RGB color
+    pub fn look(&self) -> (char, char, [u8; 3]) {
+        match self {
+            FoodType::Flakes => ('*', '.', [200, 140, 60]),
+            FoodType::Pellets => ('o', '.', [150, 90, 40]),
+            FoodType::Bloodworms => ('~', ',', [200, 30, 30]),
+            FoodType::AlgaeWafers => ('=', '_', [60, 130, 50]),
+            FoodType::BrineShrimp => ('°', '.', [240, 150, 120]),
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            FoodType::Flakes => FoodType::Pellets,
+            FoodType::Pellets => FoodType::Bloodworms,
+            FoodType::Bloodworms => FoodType::AlgaeWafers,
+            FoodType::AlgaeWafers => FoodType::BrineShrimp,
+            FoodType::BrineShrimp => FoodType::Flakes,
+        }
+    }
+}
+
+/// A piece of food drifting down through the tank
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FoodParticle {
+    #[serde(default)]
+    pub kind: FoodType,
+    pub position: (f32, f32), // (x, y) 0.0 - 1.0, same space as fish
+    #[serde(skip)]
+    pub prev_position: Option<(f32, f32)>, // Position before the last step, for smooth rendering
+    pub nutrition: f32, // Hunger points left in it
+}
+
+impl FoodParticle {
+    /// A fresh piece landing somewhere on the surface
+    pub fn drop(kind: FoodType, rng: &mut impl Rng) -> Self {
+        Self {
+            kind,
+            position: (rng.gen_range(0.15..0.85), rng.gen_range(0.0..0.05)),
+            prev_position: None,
+            nutrition: kind.nutrition(),
+        }
+    }
+
+    /// Lying on the substrate, where it rots if nobody eats it
+    pub fn settled(&self) -> bool {
+        self.position.1 >= SUBSTRATE_DEPTH
+    }
+
+    /// Position to draw at, `alpha` (0.0-1.0) of the way from the previous
+    /// step to the current one
+    pub fn render_position(&self, alpha: f32) -> (f32, f32) {
+        super::lerp_position(self.prev_position, self.position, alpha)
+    }
+}
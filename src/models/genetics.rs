@@ -0,0 +1,262 @@
+//! Heritable traits.
+//!
+//! Every fish carries two alleles per gene, one from each parent. A fry gets a
+//! random allele from each parent for every gene independently, and each
+//! allele has a small chance to mutate on the way. Numeric genes express the
+//! average of their two alleles; fin shape follows simple dominance.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Chance for each inherited allele to mutate
+const MUTATION_CHANCE: f64 = 0.05;
+
+/// A numeric gene: two alleles, expressed as their average
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Gene(pub f32, pub f32);
+
+impl Gene {
+    fn uniform(value: f32) -> Self {
+        Self(value, value)
+    }
+
+    pub fn value(&self) -> f32 {
+        (self.0 + self.1) / 2.0
+    }
+
+    /// Wild-type gene: both alleles scattered around `mean`
+    fn wild(mean: f32, spread: f32, range: (f32, f32), rng: &mut impl Rng) -> Self {
+        let mut allele = || (mean + rng.gen_range(-spread..=spread)).clamp(range.0, range.1);
+        Self(allele(), allele())
+    }
+
+    /// One allele from each parent, either of which may mutate by up to `step`
+    fn inherit(a: &Gene, b: &Gene, step: f32, range: (f32, f32), rng: &mut impl Rng) -> Self {
+        let mut pick = |gene: &Gene| {
+            let allele = if rng.gen() { gene.0 } else { gene.1 };
+            if rng.gen_bool(MUTATION_CHANCE) {
+                (allele + rng.gen_range(-step..=step)).clamp(range.0, range.1)
+            } else {
+                allele
+            }
+        };
+        Self(pick(a), pick(b))
+    }
+}
+
+/// Fin shape, most dominant first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum FinShape {
+    Standard,
+    Long,
+    Veil, // Recessive: needs both alleles
+}
+
+impl FinShape {
+    const ALL: [FinShape; 3] = [FinShape::Standard, FinShape::Long, FinShape::Veil];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FinShape::Standard => "standard",
+            FinShape::Long => "long",
+            FinShape::Veil => "veil",
+        }
+    }
+
+    /// Long fins are pretty but drag in the water
+    pub fn speed_factor(&self) -> f32 {
+        match self {
+            FinShape::Standard => 1.0,
+            FinShape::Long => 0.9,
+            FinShape::Veil => 0.8,
+        }
+    }
+
+    /// Extra tail drawn behind the sprite (right-facing, left-facing)
+    pub fn tail(&self) -> (&'static str, &'static str) {
+        match self {
+            FinShape::Standard => ("", ""),
+            FinShape::Long => ("-", "-"),
+            FinShape::Veil => ("≈", "≈"),
+        }
+    }
+}
+
+const COLOR_RANGE: (f32, f32) = (-180.0, 180.0);
+const SIZE_RANGE: (f32, f32) = (0.5, 2.0);
+const SPEED_RANGE: (f32, f32) = (0.5, 1.5);
+const HARDINESS_RANGE: (f32, f32) = (0.5, 1.5);
+const TEMPERAMENT_RANGE: (f32, f32) = (0.0, 1.0);
+
+/// The full set of heritable traits
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Genome {
+    pub color: Gene,           // Hue shift from the species color, degrees
+    pub fins: (FinShape, FinShape),
+    pub size: Gene,            // Adult size, 1.0 = typical for the species
+    pub speed: Gene,           // Swim speed multiplier
+    pub hardiness: Gene,       // Resistance to harm, 1.0 = typical
+    pub temperament: Gene,     // 0.0 = placid, 1.0 = aggressive
+}
+
+impl Default for Genome {
+    /// Perfectly average fish, used for saves from before genetics
+    fn default() -> Self {
+        Self {
+            color: Gene::uniform(0.0),
+            fins: (FinShape::Standard, FinShape::Standard),
+            size: Gene::uniform(1.0),
+            speed: Gene::uniform(1.0),
+            hardiness: Gene::uniform(1.0),
+            temperament: Gene::uniform(0.5),
+        }
+    }
+}
+
+impl Genome {
+    /// Genome of a shop-bought fish: close to average with a little variety
+    pub fn random(rng: &mut impl Rng) -> Self {
+        let mut fin = || if rng.gen_bool(0.1) { FinShape::Long } else { FinShape::Standard };
+        let fins = (fin(), fin());
+        Self {
+            color: Gene::wild(0.0, 15.0, COLOR_RANGE, rng),
+            fins,
+            size: Gene::wild(1.0, 0.1, SIZE_RANGE, rng),
+            speed: Gene::wild(1.0, 0.1, SPEED_RANGE, rng),
+            hardiness: Gene::wild(1.0, 0.1, HARDINESS_RANGE, rng),
+            temperament: Gene::wild(0.5, 0.2, TEMPERAMENT_RANGE, rng),
+        }
+    }
+
+    /// Genome of a fry of `mother` and `father`
+    pub fn inherit(mother: &Genome, father: &Genome, rng: &mut impl Rng) -> Self {
+        let mut fin = |pair: (FinShape, FinShape)| {
+            let allele = if rng.gen() { pair.0 } else { pair.1 };
+            if rng.gen_bool(MUTATION_CHANCE) {
+                FinShape::ALL[rng.gen_range(0..FinShape::ALL.len())]
+            } else {
+                allele
+            }
+        };
+        let fins = (fin(mother.fins), fin(father.fins));
+        Self {
+            color: Gene::inherit(&mother.color, &father.color, 40.0, COLOR_RANGE, rng),
+            fins,
+            size: Gene::inherit(&mother.size, &father.size, 0.2, SIZE_RANGE, rng),
+            speed: Gene::inherit(&mother.speed, &father.speed, 0.2, SPEED_RANGE, rng),
+            hardiness: Gene::inherit(&mother.hardiness, &father.hardiness, 0.2, HARDINESS_RANGE, rng),
+            temperament: Gene::inherit(&mother.temperament, &father.temperament, 0.2, TEMPERAMENT_RANGE, rng),
+        }
+    }
+
+    /// Expressed fin shape: the more dominant of the two alleles
+    pub fn fin_shape(&self) -> FinShape {
+        self.fins.0.min(self.fins.1)
+    }
+
+    /// Swim speed multiplier, including drag from the fins
+    pub fn speed_factor(&self) -> f32 {
+        self.speed.value() * self.fin_shape().speed_factor()
+    }
+
+    /// `base` RGB color with this genome's hue shift applied
+    pub fn tint(&self, base: [u8; 3]) -> [u8; 3] {
+        let shift = self.color.value();
+        if shift.abs() < 0.5 {
+            return base;
+        }
+        let [r, g, b] = base.map(|c| c as f32 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+        if chroma == 0.0 {
+            return base; // Greys have no hue to shift
+        }
+        let hue = if max == r {
+            60.0 * ((g - b) / chroma).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / chroma + 2.0)
+        } else {
+            60.0 * ((r - g) / chroma + 4.0)
+        };
+        let hue = (hue + shift).rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        [r, g, b].map(|c| ((c + min) * 255.0).round() as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::SimRng;
+
+    fn parent(value: f32, fins: FinShape) -> Genome {
+        Genome {
+            color: Gene::uniform(value * 10.0),
+            fins: (fins, fins),
+            size: Gene::uniform(value),
+            speed: Gene::uniform(value),
+            hardiness: Gene::uniform(value),
+            temperament: Gene::uniform(value / 2.0),
+        }
+    }
+
+    #[test]
+    fn fry_take_one_allele_from_each_parent() {
+        let mut rng = SimRng::new(1);
+        let mother = parent(0.8, FinShape::Standard);
+        let father = parent(1.2, FinShape::Veil);
+        let mut mutated = 0;
+        let trials = 2000;
+        for _ in 0..trials {
+            let fry = Genome::inherit(&mother, &father, &mut rng);
+            // Mutations move an allele by at most 0.2 and stay in range
+            assert!((fry.size.0 - 0.8).abs() <= 0.2 + f32::EPSILON);
+            assert!((fry.size.1 - 1.2).abs() <= 0.2 + f32::EPSILON);
+            assert!((SIZE_RANGE.0..=SIZE_RANGE.1).contains(&fry.size.0));
+            if fry.size.0 != 0.8 {
+                mutated += 1;
+            }
+        }
+        // Mutations happen, but rarely
+        let expected = (trials as f64 * MUTATION_CHANCE) as i32;
+        assert!(mutated > expected / 2 && mutated < expected * 2, "{mutated} mutations");
+    }
+
+    #[test]
+    fn veil_fins_are_recessive() {
+        let veil = parent(1.0, FinShape::Veil);
+        assert_eq!(veil.fin_shape(), FinShape::Veil);
+        let carrier = Genome { fins: (FinShape::Veil, FinShape::Standard), ..veil };
+        assert_eq!(carrier.fin_shape(), FinShape::Standard);
+        assert!(carrier.speed_factor() > veil.speed_factor());
+    }
+
+    #[test]
+    fn shop_fish_are_close_to_average() {
+        let mut rng = SimRng::new(2);
+        for _ in 0..500 {
+            let genome = Genome::random(&mut rng);
+            assert!((0.9..=1.1).contains(&genome.size.value()));
+            assert!((0.3..=0.7).contains(&genome.temperament.value()));
+            assert_ne!(genome.fin_shape(), FinShape::Veil);
+        }
+    }
+
+    #[test]
+    fn tint_shifts_hue() {
+        let average = Genome::default();
+        assert_eq!(average.tint([200, 40, 40]), [200, 40, 40]);
+        let shifted = Genome { color: Gene::uniform(120.0), ..average };
+        assert_eq!(shifted.tint([255, 0, 0]), [0, 255, 0]);
+        assert_eq!(shifted.tint([90, 90, 90]), [90, 90, 90]);
+    }
+}
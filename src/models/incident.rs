@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+use super::Fish;
+
+/// Kinds of things that can go wrong
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncidentKind {
+    HeaterStuck,
+    FilterClogged,
+    PowerOutage,
+    Heatwave,
+    JumpedOut,
+}
+
+impl IncidentKind {
+    /// Headline when it happens
+    pub fn alert(&self) -> &'static str {
+        match self {
+            IncidentKind::HeaterStuck => "🔥 A heater is stuck on",
+            IncidentKind::FilterClogged => "🚱 The filter has clogged",
+            IncidentKind::PowerOutage => "🔌 Power cut! Filter and heater are off",
+            IncidentKind::Heatwave => "☀️ Heatwave! The room is heating up",
+            IncidentKind::JumpedOut => "🐟 A fish jumped out of the tank",
+        }
+    }
+
+    /// What the player can do about it
+    pub fn response(&self) -> &'static str {
+        match self {
+            IncidentKind::HeaterStuck => "unplug the heater",
+            IncidentKind::FilterClogged => "clean the filter",
+            IncidentKind::PowerOutage => "hook up a battery backup",
+            IncidentKind::Heatwave => "set up a fan and ice packs",
+            IncidentKind::JumpedOut => "put it back",
+        }
+    }
+
+    /// Once the player has dealt with it
+    pub fn resolved(&self) -> &'static str {
+        match self {
+            IncidentKind::HeaterStuck => "🔌 Heater unplugged and put in the cupboard to reset",
+            IncidentKind::FilterClogged => "🧽 Filter cleaned (some bacteria rinsed out too)",
+            IncidentKind::PowerOutage => "🔋 Every tank is running off a battery backup",
+            IncidentKind::Heatwave => "🧊 A fan and ice packs are keeping the tank cooler",
+            IncidentKind::JumpedOut => "🐟 Back in the water, shaken but alive",
+        }
+    }
+
+    /// When it ends on its own
+    pub fn over(&self) -> &'static str {
+        match self {
+            IncidentKind::HeaterStuck => "🔧 The stuck heater is out of the tank",
+            IncidentKind::FilterClogged => "🧽 The filter is flowing again",
+            IncidentKind::PowerOutage => "⚡ The power is back",
+            IncidentKind::Heatwave => "🌤️ The heatwave has broken",
+            IncidentKind::JumpedOut => "🐟 The fish is back in the water",
+        }
+    }
+}
+
+/// Something going wrong in a tank until the player deals with it or it
+/// runs its course
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Incident {
+    HeaterStuck, // Thermostat stuck on, cooking the tank
+    FilterClogged,
+    PowerOutage { hours_left: f32 },
+    Heatwave {
+        hours_left: f32,
+        #[serde(default)]
+        cooled: bool, // Fan and ice packs set up, so the tank warms less
+    },
+    JumpedOut { fish: Box<Fish>, hours_left: f32 }, // Flopping on the floor until put back or dead
+}
+
+impl Incident {
+    pub fn kind(&self) -> IncidentKind {
+        match self {
+            Incident::HeaterStuck => IncidentKind::HeaterStuck,
+            Incident::FilterClogged => IncidentKind::FilterClogged,
+            Incident::PowerOutage { .. } => IncidentKind::PowerOutage,
+            Incident::Heatwave { .. } => IncidentKind::Heatwave,
+            Incident::JumpedOut { .. } => IncidentKind::JumpedOut,
+        }
+    }
+
+    /// Still waiting for the player to do something about it
+    pub fn needs_response(&self) -> bool {
+        !matches!(self, Incident::Heatwave { cooled: true, .. })
+    }
+}
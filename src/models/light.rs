@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+/// Sunlight reaching the tank through the window while the sun is up
+const DAYLIGHT: f32 = 0.1;
+
+/// Below this much light fish settle down to sleep and the tank looks dark
+pub const DARK: f32 = 0.2;
+
+/// Dimmest the fixture goes, and how far one press turns it up or down. A
+/// lamp that is on always gives enough light to count as lit.
+const MIN_INTENSITY: f32 = DARK;
+const INTENSITY_STEP: f32 = 0.1;
+
+/// What can be set on a light
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightSetting {
+    OnAt,
+    OffAt,
+    Intensity,
+}
+
+impl LightSetting {
+    pub const ALL: [LightSetting; 3] = [LightSetting::OnAt, LightSetting::OffAt, LightSetting::Intensity];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LightSetting::OnAt => "On at",
+            LightSetting::OffAt => "Off at",
+            LightSetting::Intensity => "Intensity",
+        }
+    }
+}
+
+/// Light fixture over the tank. Its timer follows the clock but not the
+/// sun, so a tank can be lit in the evening when someone is watching.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Light {
+    pub on_hour: u8,    // 0 - 23
+    pub off_hour: u8,   // 0 - 23, earlier than `on_hour` to stay on past midnight
+    pub intensity: f32, // 0.2 - 1.0
+}
+
+impl Default for Light {
+    fn default() -> Self {
+        Self {
+            on_hour: 6,
+            off_hour: 18,
+            intensity: 1.0,
+        }
+    }
+}
+
+impl Light {
+    pub fn is_on(&self, hour: u8) -> bool {
+        if self.on_hour <= self.off_hour {
+            (self.on_hour..self.off_hour).contains(&hour)
+        } else {
+            hour >= self.on_hour || hour < self.off_hour
+        }
+    }
+
+    /// Hours a day the light is on
+    pub fn photoperiod(&self) -> u8 {
+        (self.off_hour + 24 - self.on_hour) % 24
+    }
+
+    /// Light (0.0 - 1.0) in the tank at `hour`, counting what comes in
+    /// through the window
+    pub fn level(&self, hour: u8, sun_up: bool) -> f32 {
+        let lamp = if self.is_on(hour) { self.intensity } else { 0.0 };
+        let window = if sun_up { DAYLIGHT } else { 0.0 };
+        lamp.max(window)
+    }
+
+    /// Move `setting` by `step` hours or intensity steps
+    pub fn adjust(&mut self, setting: LightSetting, step: i32) {
+        let shift = |hour: u8| (hour as i32 + step).rem_euclid(24) as u8;
+        match setting {
+            LightSetting::OnAt => self.on_hour = shift(self.on_hour),
+            LightSetting::OffAt => self.off_hour = shift(self.off_hour),
+            LightSetting::Intensity => {
+                let intensity = self.intensity + step as f32 * INTENSITY_STEP;
+                // Round so repeated steps land back on whole tenths
+                self.intensity = ((intensity * 10.0).round() / 10.0).clamp(MIN_INTENSITY, 1.0);
+            }
+        }
+    }
+}
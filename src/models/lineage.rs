@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::fish::{DeathCause, Fish, Gender};
+use super::species::Species;
+
+/// The family details of a fish. Saved when a fish dies so family trees
+/// still reach it after it's gone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineageRecord {
+    pub id: Uuid,
+    pub name: String,
+    pub species: Species,
+    pub gender: Gender,
+    pub parents: Option<(Uuid, Uuid)>, // (mother, father); None for shop-bought fish
+    pub generation: u32,
+    pub born_at: f64,          // Game clock at birth
+    #[serde(default)]
+    pub age: Option<f64>,      // Game seconds old when recorded; None in older saves
+    pub alive: bool,
+    pub died_at: Option<f64>,  // Game clock at death, if it died after lineage was tracked
+    pub cause_of_death: Option<DeathCause>,
+}
+
+impl LineageRecord {
+    /// Snapshot of `fish`; `died_at` is the game clock if it has just died
+    pub fn of(fish: &Fish, died_at: Option<f64>) -> Self {
+        Self {
+            id: fish.id,
+            name: fish.name.clone(),
+            species: fish.species.clone(),
+            gender: fish.gender,
+            parents: fish.parents,
+            generation: fish.generation,
+            born_at: fish.born_at,
+            age: Some(fish.age.num_milliseconds() as f64 / 1000.0),
+            alive: fish.alive,
+            died_at,
+            cause_of_death: fish.cause_of_death,
+        }
+    }
+
+    /// Game clock at birth going by the fish's own age, counted back from its
+    /// death or from `now`. Fish don't age while the clock runs on past the
+    /// offline cap, so this keeps birth days in step with ages.
+    pub fn birth_clock(&self, now: f64) -> f64 {
+        match self.age {
+            Some(age) => self.died_at.unwrap_or(now) - age,
+            None => self.born_at,
+        }
+    }
+
+    pub fn is_child_of(&self, id: Uuid) -> bool {
+        self.parents.is_some_and(|(mother, father)| mother == id || father == id)
+    }
+}
@@ -1,5 +1,42 @@
 pub mod fish;
+pub mod genetics;
+pub mod lineage;
 pub mod decoration;
+pub mod equipment;
+pub mod disease;
+pub mod food;
+pub mod incident;
+pub mod light;
+pub mod species;
+pub mod substrate;
+pub mod tank_size;
 
-pub use fish::{Fish, FishState, Species, GrowthStage, Gender};
+pub use fish::{DeathCause, Fish, FishState, SpeciesProfile, GrowthStage, Gender, Surroundings};
+pub use species::{registry, Species, SpeciesDef, SpeciesRegistry};
+pub use genetics::{FinShape, Gene, Genome};
+pub use lineage::LineageRecord;
 pub use decoration::{Decoration, DecorationType};
+pub use equipment::{Equipment, EquipmentItem, EquipmentKind, EquipmentModel};
+pub use disease::{Disease, Infection, Medication, Treatment};
+pub use food::{FoodParticle, FoodType};
+pub use incident::{Incident, IncidentKind};
+pub use light::{Light, LightSetting};
+pub use substrate::Substrate;
+pub use tank_size::TankSize;
+
+use rand::Rng;
+use uuid::Uuid;
+
+/// Build a v4 UUID from the caller's RNG so ids are reproducible from a seed
+fn random_id(rng: &mut impl Rng) -> Uuid {
+    uuid::Builder::from_random_bytes(rng.gen()).into_uuid()
+}
+
+/// Point `alpha` (0.0-1.0) of the way from `prev` to `cur`; `cur` when there
+/// is no previous position yet
+fn lerp_position(prev: Option<(f32, f32)>, cur: (f32, f32), alpha: f32) -> (f32, f32) {
+    match prev {
+        Some((px, py)) => (px + (cur.0 - px) * alpha, py + (cur.1 - py) * alpha),
+        None => cur,
+    }
+}
@@ -0,0 +1,422 @@
+//! Species definitions.
+//!
+//! Every species is described by data rather than code: starting stats, the
+//! water it needs, favourite foods, colors and sprites for each growth stage. The built-in
+//! species ship in `species.toml`; players can add or replace species by
+//! dropping more TOML files into the `species` folder of the config directory.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use super::fish::{GrowthStage, SpeciesProfile};
+use super::food::FoodType;
+
+const BUILT_IN: &str = include_str!("species.toml");
+
+static REGISTRY: OnceLock<SpeciesRegistry> = OnceLock::new();
+
+/// A fish species, referred to by the `id` of its definition
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Species(String);
+
+impl Species {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+
+    /// Definition for this species. Fish whose species file has since been
+    /// removed fall back to the first registered species.
+    pub fn def(&self) -> &'static SpeciesDef {
+        let registry = registry();
+        registry.get(&self.0).unwrap_or(&registry.species[0])
+    }
+
+    pub fn name(&self) -> &'static str {
+        &self.def().name
+    }
+
+    pub fn profile(&self) -> SpeciesProfile {
+        self.def().needs
+    }
+
+    /// Whether fish of this species pick on fish of `other`
+    pub fn harasses(&self, other: &Species) -> bool {
+        let behavior = &self.def().behavior;
+        if self == other {
+            behavior.territorial
+        } else {
+            behavior.harasses.iter().any(|id| id == other.id())
+        }
+    }
+
+    /// Whether `food` is one of the species' favourites
+    pub fn likes(&self, food: FoodType) -> bool {
+        self.def().favorite_foods.contains(&food)
+    }
+
+    /// Whether the two can share a tank without either bullying the other
+    pub fn compatible_with(&self, other: &Species) -> bool {
+        !self.harasses(other) && !other.harasses(self)
+    }
+}
+
+/// Everything that makes up one species, as written in a species file
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpeciesDef {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_emoji")]
+    pub emoji: String,
+    pub color: [u8; 3], // RGB
+    #[serde(default)]
+    pub favorite_foods: Vec<FoodType>,
+    pub stats: StartingStats,
+    pub needs: SpeciesProfile,
+    #[serde(default)]
+    pub behavior: Behavior,
+    pub sprites: StageSprites,
+}
+
+/// How a species treats its tank mates
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Behavior {
+    #[serde(default)]
+    pub aggression: f32,       // 0.0 = peaceful, 1.0 = vicious
+    #[serde(default)]
+    pub territorial: bool,     // Fights its own kind
+    #[serde(default)]
+    pub harasses: Vec<String>, // Ids of species it chases and nips
+}
+
+fn default_emoji() -> String {
+    "🐟".to_string()
+}
+
+/// Stats a newly added fish starts with
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct StartingStats {
+    pub hunger: f32,
+    pub happiness: f32,
+    pub energy: f32,
+    pub speed: f32, // Initial swim velocity
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StageSprites {
+    pub fry: Sprite,
+    pub juvenile: Sprite,
+    pub adult: Sprite,
+    pub elder: Option<Sprite>, // Adult sprite in faded colors when left out
+}
+
+/// Lines of a sprite facing each way, plus an optional color override
+#[derive(Debug, Clone, Deserialize)]
+pub struct Sprite {
+    pub right: Vec<String>,
+    #[serde(default)]
+    pub left: Vec<String>, // Mirrored from `right` when left out
+    pub color: Option<[u8; 3]>,
+}
+
+impl SpeciesDef {
+    pub fn sprite(&self, stage: GrowthStage) -> &Sprite {
+        match stage {
+            GrowthStage::Fry => &self.sprites.fry,
+            GrowthStage::Juvenile => &self.sprites.juvenile,
+            GrowthStage::Adult => &self.sprites.adult,
+            GrowthStage::Elder => self.sprites.elder.as_ref().unwrap_or(&self.sprites.adult),
+        }
+    }
+
+    /// RGB color to draw this species at a given stage
+    pub fn color(&self, stage: GrowthStage) -> [u8; 3] {
+        match (stage, &self.sprites.elder) {
+            (GrowthStage::Elder, Some(Sprite { color: Some(color), .. })) => *color,
+            // Elders fade toward grey
+            (GrowthStage::Elder, _) => self.color.map(|c| ((c as u16 + 2 * 150) / 3) as u8),
+            _ => self.sprite(stage).color.unwrap_or(self.color),
+        }
+    }
+
+    /// Fill in anything the file was allowed to leave out and reject
+    /// definitions that can't be drawn
+    fn finish(mut self) -> Result<Self, String> {
+        if self.id.trim().is_empty() {
+            return Err("species without an id".to_string());
+        }
+        for (stage, sprite) in [
+            ("fry", Some(&mut self.sprites.fry)),
+            ("juvenile", Some(&mut self.sprites.juvenile)),
+            ("adult", Some(&mut self.sprites.adult)),
+            ("elder", self.sprites.elder.as_mut()),
+        ] {
+            let Some(sprite) = sprite else { continue };
+            if sprite.right.is_empty() {
+                return Err(format!("{}: {} sprite has no lines", self.id, stage));
+            }
+            if sprite.left.is_empty() {
+                let width = sprite.right.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+                sprite.left = sprite.right.iter().map(|line| mirror(line, width)).collect();
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// Flip a sprite line horizontally within a sprite `width` characters wide,
+/// swapping characters that have a left/right counterpart
+fn mirror(line: &str, width: usize) -> String {
+    let padding = width.saturating_sub(line.chars().count());
+    line.chars()
+        .chain(std::iter::repeat_n(' ', padding))
+        .rev()
+        .map(|c| match c {
+            '<' => '>',
+            '>' => '<',
+            '(' => ')',
+            ')' => '(',
+            '[' => ']',
+            ']' => '[',
+            '{' => '}',
+            '}' => '{',
+            '/' => '\\',
+            '\\' => '/',
+            '«' => '»',
+            '»' => '«',
+            other => other,
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct SpeciesFile {
+    #[serde(default)]
+    species: Vec<SpeciesDef>,
+}
+
+/// All known species, in the order new fish cycle through them
+#[derive(Debug)]
+pub struct SpeciesRegistry {
+    species: Vec<SpeciesDef>,
+    index: HashMap<String, usize>,
+}
+
+impl SpeciesRegistry {
+    /// Just the species that ship with the game
+    pub fn built_in() -> Self {
+        let mut registry = Self { species: Vec::new(), index: HashMap::new() };
+        registry
+            .add_file(BUILT_IN)
+            .expect("built-in species.toml is valid");
+        registry
+    }
+
+    /// Built-in species plus every `*.toml` file in `dir`. Files that fail to
+    /// load are skipped and reported in the returned list.
+    pub fn load(dir: &Path) -> (Self, Vec<String>) {
+        let mut registry = Self::built_in();
+        let mut errors = Vec::new();
+
+        let mut paths: Vec<_> = match fs::read_dir(dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+                .collect(),
+            Err(_) => Vec::new(), // No custom species
+        };
+        paths.sort(); // Load order decides overrides, so keep it stable
+
+        for path in paths {
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            let result = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| registry.add_file(&content));
+            if let Err(e) = result {
+                errors.push(format!("{}: {}", file_name, e));
+            }
+        }
+
+        (registry, errors)
+    }
+
+    /// Parse a species file and add its species, replacing any with the same id
+    fn add_file(&mut self, content: &str) -> Result<(), String> {
+        let file: SpeciesFile = toml::from_str(content).map_err(|e| e.message().replace('\n', ": "))?;
+        let defs = file
+            .species
+            .into_iter()
+            .map(SpeciesDef::finish)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for def in defs {
+            match self.index.get(&def.id) {
+                Some(&i) => self.species[i] = def,
+                None => {
+                    self.index.insert(def.id.clone(), self.species.len());
+                    self.species.push(def);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&SpeciesDef> {
+        self.index.get(id).map(|&i| &self.species[i])
+    }
+
+    pub fn all(&self) -> &[SpeciesDef] {
+        &self.species
+    }
+}
+
+/// The active species registry. Only the built-in species unless
+/// [`install_registry`] ran first.
+pub fn registry() -> &'static SpeciesRegistry {
+    REGISTRY.get_or_init(SpeciesRegistry::built_in)
+}
+
+/// Make `registry` the active one. Has to happen before anything looks up a
+/// species; returns `false` if the registry was already in use.
+pub fn install_registry(registry: SpeciesRegistry) -> bool {
+    REGISTRY.set(registry).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A species file that replaces the guppy and adds a platy
+    const CUSTOM: &str = r#"
+[[species]]
+id = "Guppy"
+name = "Fancy Guppy"
+color = [250, 120, 200]
+
+[species.stats]
+hunger = 70.0
+happiness = 85.0
+energy = 100.0
+speed = 0.015
+
+[species.needs]
+temperature = [22.0, 28.0]
+ph = [6.8, 8.5]
+activity = 1.3
+hunger_rate = 1.2
+lifespan_days = 20.0
+min_tank_litres = 40.0
+adult_cm = 4.0
+waste = 0.8
+
+[species.sprites.fry]
+right = [".>"]
+
+[species.sprites.juvenile]
+right = [">°>"]
+
+[species.sprites.adult]
+right = ["><(°>"]
+
+[[species]]
+id = "Platy"
+name = "Platy"
+color = [255, 140, 0]
+
+[species.stats]
+hunger = 70.0
+happiness = 80.0
+energy = 100.0
+speed = 0.012
+
+[species.needs]
+temperature = [20.0, 26.0]
+ph = [7.0, 8.2]
+activity = 1.0
+hunger_rate = 1.0
+lifespan_days = 25.0
+min_tank_litres = 40.0
+adult_cm = 5.0
+waste = 0.9
+
+[species.sprites.fry]
+right = [".>"]
+
+[species.sprites.juvenile]
+right = ["><>"]
+
+[species.sprites.adult]
+right = ["><{°>"]
+"#;
+
+    #[test]
+    fn built_in_species_parse() {
+        let registry = SpeciesRegistry::built_in();
+        let ids: Vec<&str> = registry.all().iter().map(|def| def.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            ["Goldfish", "Betta", "Guppy", "NeonTetra", "Angelfish", "Clownfish", "Koi", "Pufferfish"]
+        );
+        for def in registry.all() {
+            for stage in [GrowthStage::Fry, GrowthStage::Juvenile, GrowthStage::Adult, GrowthStage::Elder] {
+                let sprite = def.sprite(stage);
+                assert!(!sprite.right.is_empty() && sprite.left.len() == sprite.right.len(), "{}", def.id);
+            }
+        }
+    }
+
+    #[test]
+    fn custom_file_replaces_and_adds_species() {
+        let mut registry = SpeciesRegistry::built_in();
+        let count = registry.all().len();
+        registry.add_file(CUSTOM).unwrap();
+
+        assert_eq!(registry.all().len(), count + 1);
+        assert_eq!(registry.get("Guppy").unwrap().name, "Fancy Guppy");
+        assert_eq!(registry.all()[2].id, "Guppy", "replaced in place");
+        assert_eq!(registry.all().last().unwrap().id, "Platy");
+        // Left-facing sprites are mirrored when left out
+        assert_eq!(registry.get("Platy").unwrap().sprites.adult.left, ["<°}><"]);
+    }
+
+    #[test]
+    fn bad_files_are_rejected_whole() {
+        let mut registry = SpeciesRegistry::built_in();
+        assert!(registry.add_file("[[species]]\nid = \"Broken\"").is_err());
+        let no_sprite = CUSTOM.replace("right = [\"><{°>\"]", "right = []");
+        assert!(registry.add_file(&no_sprite).is_err());
+        assert!(registry.get("Platy").is_none());
+        assert_eq!(registry.get("Guppy").unwrap().name, "Guppy");
+    }
+
+    #[test]
+    fn load_merges_the_species_folder() {
+        let dir = std::env::temp_dir().join(format!("fishtank-species-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("custom.toml"), CUSTOM).unwrap();
+        fs::write(dir.join("broken.toml"), "not toml [").unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let (registry, errors) = SpeciesRegistry::load(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(registry.get("Platy").is_some());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("broken.toml"));
+    }
+
+    #[test]
+    fn unknown_species_fall_back_without_panicking() {
+        let lost = Species::new("Coelacanth");
+        assert_eq!(lost.def().id, registry().all()[0].id);
+        assert!(!lost.name().is_empty());
+        assert_eq!(lost.id(), "Coelacanth", "the save keeps its own id");
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// Material covering the tank floor. Some substrates buffer the pH.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Substrate {
+    #[default]
+    Gravel,
+    Sand,
+    CrushedCoral, // Dissolves slowly, pushes pH up
+    AquaSoil,     // Releases humic acids, pulls pH down
+}
+
+impl Substrate {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Substrate::Gravel => "Gravel",
+            Substrate::Sand => "Sand",
+            Substrate::CrushedCoral => "Crushed Coral",
+            Substrate::AquaSoil => "Aqua Soil",
+        }
+    }
+
+    /// pH the water settles at over this substrate with no other influences
+    pub fn base_ph(&self) -> f32 {
+        match self {
+            Substrate::Gravel => 7.0,
+            Substrate::Sand => 7.2,
+            Substrate::CrushedCoral => 8.0,
+            Substrate::AquaSoil => 6.4,
+        }
+    }
+
+    /// How strongly the substrate holds the pH at its base (0.0 - 1.0)
+    pub fn buffering(&self) -> f32 {
+        match self {
+            Substrate::Gravel | Substrate::Sand => 0.2,
+            Substrate::CrushedCoral | Substrate::AquaSoil => 0.6,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Substrate::Gravel => Substrate::Sand,
+            Substrate::Sand => Substrate::CrushedCoral,
+            Substrate::CrushedCoral => Substrate::AquaSoil,
+            Substrate::AquaSoil => Substrate::Gravel,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+/// Water volume the simulation is tuned for; smaller tanks swing faster
+const REFERENCE_LITRES: f32 = 100.0;
+
+/// Size of the glass box. Less water means waste and heat build up faster,
+/// so small tanks are harder to keep stable.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum TankSize {
+    Nano,     // 20 L desktop tank, very unforgiving
+    Small,    // 60 L
+    #[default]
+    Standard, // 100 L
+    Large,    // 200 L, big enough for koi
+}
+
+impl TankSize {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TankSize::Nano => "Nano",
+            TankSize::Small => "Small",
+            TankSize::Standard => "Standard",
+            TankSize::Large => "Large",
+        }
+    }
+
+    /// (length, depth, height) in centimetres
+    pub fn dimensions(&self) -> (f32, f32, f32) {
+        match self {
+            TankSize::Nano => (40.0, 20.0, 25.0),
+            TankSize::Small => (60.0, 25.0, 40.0),
+            TankSize::Standard => (80.0, 30.0, 42.0),
+            TankSize::Large => (100.0, 40.0, 50.0),
+        }
+    }
+
+    pub fn litres(&self) -> f32 {
+        let (length, depth, height) = self.dimensions();
+        length * depth * height / 1000.0
+    }
+
+    /// How much faster concentrations change than in a 100 L tank
+    pub fn swing(&self) -> f32 {
+        REFERENCE_LITRES / self.litres()
+    }
+
+    /// Share (0.0 - 1.0) of the largest tank's front pane this one covers,
+    /// as (width, height)
+    pub fn front_pane(&self) -> (f32, f32) {
+        let (length, _, height) = self.dimensions();
+        let (max_length, _, max_height) = TankSize::Large.dimensions();
+        (length / max_length, height / max_height)
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TankSize::Nano => TankSize::Small,
+            TankSize::Small => TankSize::Standard,
+            TankSize::Standard => TankSize::Large,
+            TankSize::Large => TankSize::Nano,
+        }
+    }
+}
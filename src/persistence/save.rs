@@ -4,7 +4,8 @@ use std::path::PathBuf;
 use anyhow::Result;
 use std::fs;
 
-use crate::models::Fish;
+use crate::models::{Equipment, EquipmentItem, EquipmentModel, Fish, FoodParticle, Incident, Light, LineageRecord, Substrate, TankSize, Treatment};
+use crate::simulation::SimRng;
 
 /// Water quality parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +13,28 @@ pub struct WaterParams {
     pub purity: f32,       // 0.0 - 100.0 (General cleanliness)
     pub ph: f32,           // 0.0 - 14.0 (Acidity/Alkalinity, Ideal: 7.0)
     pub temperature: f32,  // Celsius (Ideal: 24-26)
+    #[serde(default)]
+    pub ammonia: f32,      // ppm (Toxic above 0.25)
+    #[serde(default)]
+    pub nitrite: f32,      // ppm (Toxic above 0.5)
+    #[serde(default)]
+    pub nitrate: f32,      // ppm (Stressful above 40)
+    #[serde(default)]
+    pub waste: f32,        // Uneaten food and detritus rotting into ammonia
+    #[serde(default = "established_colony")]
+    pub bacteria: f32,     // 0.0 - 100.0 (Nitrifying colony, 0 = uncycled tank)
+    #[serde(default = "aerated")]
+    pub oxygen: f32,       // mg/L dissolved (Gasping below 4)
+}
+
+/// Tanks saved before the nitrogen cycle existed have been running a while
+fn established_colony() -> f32 {
+    50.0
+}
+
+/// Fresh water at room temperature holds about this much oxygen
+fn aerated() -> f32 {
+    8.0
 }
 
 impl Default for WaterParams {
@@ -20,26 +43,72 @@ impl Default for WaterParams {
             purity: 100.0,
             ph: 7.0,
             temperature: 25.0,
+            ammonia: 0.0,
+            nitrite: 0.0,
+            nitrate: 0.0,
+            waste: 0.0,
+            bacteria: 0.0, // New tanks need cycling
+            oxygen: aerated(),
         }
     }
 }
 
-/// Tank equipment status
+/// Names given to new tanks, in order; later tanks are numbered
+const TANK_NAMES: [&str; 3] = ["Community", "Breeding", "Quarantine"];
+
+/// One tank and everything in it. Each tank has its own water and
+/// equipment and is simulated on its own.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Equipment {
-    pub has_filter: bool,      // Reduces purity loss by 50%
-    pub has_heater: bool,      // Reduces temperature drift by 80%
-    pub has_plants: bool,      // Reduces purity loss by 10%
+pub struct Tank {
+    pub name: String,
+    #[serde(default)] // Glass box size; sets the water volume
+    pub size: TankSize,
+    pub fish: Vec<Fish>,
+    #[serde(default)] // Food sinking through the water or lying on the substrate
+    pub food: Vec<FoodParticle>,
+    #[serde(default)]
+    pub decorations: Vec<crate::models::Decoration>,
+    #[serde(default)]
+    pub algae_level: f32, // 0.0 to 100.0
+    #[serde(default)]
+    pub water: WaterParams,
+    #[serde(default)]
+    pub equipment: Equipment,
+    #[serde(default)]
+    pub substrate: Substrate,
+    #[serde(default)] // Light fixture and its timer
+    pub light: Light,
+    #[serde(default)] // Medication course currently in the water
+    pub treatment: Option<Treatment>,
+    #[serde(default)] // Things going wrong that haven't been dealt with
+    pub incidents: Vec<Incident>,
 }
 
-impl Default for Equipment {
-    fn default() -> Self {
+impl Tank {
+    /// An empty tank of fresh, uncycled water
+    pub fn new(name: impl Into<String>) -> Self {
         Self {
-            has_filter: false,
-            has_heater: false,
-            has_plants: false,
+            name: name.into(),
+            size: TankSize::default(),
+            fish: Vec::new(),
+            food: Vec::new(),
+            decorations: Vec::new(),
+            algae_level: 0.0,
+            water: WaterParams::default(),
+            equipment: Equipment::default(),
+            substrate: Substrate::default(),
+            light: Light::default(),
+            treatment: None,
+            incidents: Vec::new(),
         }
     }
+
+    /// Name for the `index`th tank of a save (0-based)
+    pub fn default_name(index: usize) -> String {
+        TANK_NAMES
+            .get(index)
+            .map_or_else(|| format!("Tank {}", index + 1), |name| name.to_string())
+    }
 }
 
 /// Main save data structure
@@ -47,22 +116,22 @@ impl Default for Equipment {
 pub struct SaveData {
     pub version: String,
     pub last_saved: DateTime<Utc>,
-    pub fish: Vec<Fish>,
-    #[serde(default)]
-    pub decorations: Vec<crate::models::Decoration>,
+    pub tanks: Vec<Tank>,  // Never empty
     #[serde(default)]
-    pub algae_level: f32, // 0.0 to 100.0
+    pub active_tank: usize, // Tank shown on screen, and the one actions apply to
+    #[serde(default)] // Family records of fish that have died
+    pub ancestors: Vec<LineageRecord>,
+    #[serde(default)] // Spare equipment in the cupboard, not in any tank
+    pub storage: Vec<EquipmentItem>,
     pub player_name: String,
     #[serde(default)]
-    pub water: WaterParams,
-    #[serde(default)]
     pub total_time: f64,
-    #[serde(default)]
-    pub equipment: Equipment,
     #[serde(default)] // Toggle for pause/weekend mode
     pub is_frozen: bool,
     #[serde(default)] // Toggle for theme selection
     pub theme_index: usize,
+    #[serde(default)] // Every random draw in the simulation comes from here
+    pub rng: SimRng,
 }
 
 impl Default for SaveData {
@@ -70,26 +139,47 @@ impl Default for SaveData {
         Self {
             version: env!("CARGO_PKG_VERSION").to_string(),
             last_saved: Utc::now(),
-            fish: Vec::new(),
-            decorations: Vec::new(),
-            algae_level: 0.0,
+            tanks: vec![Tank::new(Tank::default_name(0))],
+            active_tank: 0,
+            ancestors: Vec::new(),
+            storage: Vec::new(),
             player_name: "Player".to_string(),
-            water: WaterParams::default(),
             total_time: 0.0,
-            equipment: Equipment::default(),
             is_frozen: false,
             theme_index: 0,
+            rng: SimRng::default(),
         }
     }
 }
 
 impl SaveData {
-    /// Get the save file path
-    pub fn get_save_path() -> Result<PathBuf> {
-        let config_dir = directories::ProjectDirs::from("", "", "fishtank")
+    /// Fresh save whose simulation is driven by a fixed seed
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: SimRng::new(seed),
+            ..Self::default()
+        }
+    }
+
+    /// The tank on screen
+    pub fn tank(&self) -> &Tank {
+        &self.tanks[self.active_tank]
+    }
+
+    pub fn tank_mut(&mut self) -> &mut Tank {
+        &mut self.tanks[self.active_tank]
+    }
+
+    /// Directory holding the save file and custom species
+    pub fn config_dir() -> PathBuf {
+        directories::ProjectDirs::from("", "", "fishtank")
             .map(|dirs| dirs.config_dir().to_path_buf())
-            .unwrap_or_else(|| PathBuf::from(".fishtank"));
+            .unwrap_or_else(|| PathBuf::from(".fishtank"))
+    }
 
+    /// Get the save file path
+    pub fn get_save_path() -> Result<PathBuf> {
+        let config_dir = Self::config_dir();
         fs::create_dir_all(&config_dir)?;
         Ok(config_dir.join("save.json"))
     }
@@ -105,7 +195,7 @@ impl SaveData {
         let content = fs::read_to_string(&path)?;
         
         // Try to load save file, if it fails (old format), backup and start fresh
-        match serde_json::from_str::<SaveData>(&content) {
+        match Self::parse(&content) {
             Ok(save) => Ok(save),
             Err(_e) => {
                 // Backup old save file
@@ -118,6 +208,71 @@ impl SaveData {
         }
     }
 
+    /// Read a save file's contents, bringing older formats up to date
+    fn parse(content: &str) -> serde_json::Result<Self> {
+        let value = serde_json::from_str::<serde_json::Value>(content)?;
+        let mut save: SaveData = serde_json::from_value(Self::migrate(value))?;
+        if save.tanks.is_empty() {
+            save.tanks.push(Tank::new(Tank::default_name(0)));
+        }
+        save.active_tank = save.active_tank.min(save.tanks.len() - 1);
+        Ok(save)
+    }
+
+    /// Fill in fields that older save files are missing before deserializing
+    fn migrate(mut value: serde_json::Value) -> serde_json::Value {
+        // Saves from before multiple tanks: everything was in the one tank
+        if let Some(save) = value.as_object_mut().filter(|save| !save.contains_key("tanks")) {
+            let mut tank = serde_json::Map::new();
+            tank.insert("name".to_string(), Tank::default_name(0).into());
+            for key in ["fish", "food", "decorations", "algae_level", "water", "equipment", "substrate", "treatment"] {
+                if let Some(field) = save.remove(key) {
+                    tank.insert(key.to_string(), field);
+                }
+            }
+            save.insert("tanks".to_string(), serde_json::Value::Array(vec![tank.into()]));
+        }
+
+        let tanks = value.get_mut("tanks").and_then(|t| t.as_array_mut()).into_iter().flatten();
+        for tank in tanks.filter_map(|tank| tank.as_object_mut()) {
+            // Saves from before the equipment inventory had an on/off switch
+            // for each kind; the old filter and heater match these models
+            let switches = tank.get("equipment").filter(|e| e.get("items").is_none()).cloned();
+            if let Some(switches) = switches {
+                let installed = |key: &str| switches.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+                let items: Vec<EquipmentItem> = [
+                    ("has_filter", EquipmentModel::HangOnFilter),
+                    ("has_heater", EquipmentModel::Heater100W),
+                    ("has_plants", EquipmentModel::LivePlants),
+                ]
+                .into_iter()
+                .filter(|(key, _)| installed(key))
+                .map(|(_, model)| EquipmentItem::new(model))
+                .collect();
+                let equipment = serde_json::to_value(Equipment { items }).unwrap_or_default();
+                tank.insert("equipment".to_string(), equipment);
+            }
+
+            let Some(fish) = tank.get_mut("fish").and_then(|f| f.as_array_mut()) else { continue };
+            for fish in fish.iter_mut().filter_map(|f| f.as_object_mut()) {
+                if fish.contains_key("gender") {
+                    continue;
+                }
+                // Saves from before breeding: derive gender from the id so
+                // reloading the same file always gives the same answer
+                let odd = fish
+                    .get("id")
+                    .and_then(|id| id.as_str())
+                    .and_then(|id| id.chars().last())
+                    .and_then(|c| c.to_digit(16))
+                    .is_some_and(|d| d % 2 == 1);
+                let gender = if odd { "Female" } else { "Male" };
+                fish.insert("gender".to_string(), gender.into());
+            }
+        }
+        value
+    }
+
     /// Save data to disk
     pub fn save(&mut self) -> Result<()> {
         self.last_saved = Utc::now();
@@ -137,3 +292,67 @@ impl SaveData {
         Utc::now().signed_duration_since(self.last_saved)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Gender;
+
+    /// A save written by the game before it had multiple tanks, an equipment
+    /// inventory or species files
+    const BASELINE_SAVE: &str = include_str!("fixtures/baseline_save.json");
+
+    #[test]
+    fn baseline_save_moves_into_one_tank() {
+        let save = SaveData::parse(BASELINE_SAVE).unwrap();
+        assert_eq!(save.tanks.len(), 1);
+        assert_eq!(save.active_tank, 0);
+        assert_eq!(save.total_time, 5000.0);
+
+        let tank = save.tank();
+        assert_eq!(tank.name, Tank::default_name(0));
+        assert_eq!(tank.water.purity, 42.0);
+        assert_eq!(tank.water.temperature, 25.0);
+
+        let fish: Vec<(&str, &str, Gender)> =
+            tank.fish.iter().map(|f| (f.name.as_str(), f.species.id(), f.gender)).collect();
+        assert_eq!(
+            fish,
+            [
+                ("A", "Goldfish", Gender::Male),
+                ("B", "NeonTetra", Gender::Female),
+                ("C", "Pufferfish", Gender::Female),
+            ]
+        );
+        assert!(tank.fish.iter().all(|f| f.alive));
+    }
+
+    #[test]
+    fn baseline_switches_become_installed_equipment() {
+        let save = SaveData::parse(BASELINE_SAVE).unwrap();
+        let models: Vec<EquipmentModel> = save.tank().equipment.items.iter().map(|item| item.model).collect();
+        assert_eq!(models, [EquipmentModel::HangOnFilter, EquipmentModel::Heater100W]);
+        assert!(save.tank().equipment.items.iter().all(|item| item.wear == 0.0));
+        assert!(save.storage.is_empty());
+    }
+
+    #[test]
+    fn inventory_saves_are_left_alone() {
+        let mut save = SaveData::parse(BASELINE_SAVE).unwrap();
+        save.tank_mut().equipment.items[0].wear = 55.0;
+        let reloaded = SaveData::parse(&serde_json::to_string(&save).unwrap()).unwrap();
+        assert_eq!(reloaded.tank().equipment.items.len(), 2);
+        assert_eq!(reloaded.tank().equipment.items[0].wear, 55.0);
+    }
+
+    #[test]
+    fn fish_without_a_gender_get_the_same_one_every_load() {
+        let mut value: serde_json::Value = serde_json::from_str(BASELINE_SAVE).unwrap();
+        for fish in value["fish"].as_array_mut().unwrap() {
+            fish.as_object_mut().unwrap().remove("gender");
+        }
+        let content = value.to_string();
+        let genders = |save: SaveData| save.tank().fish.iter().map(|f| f.gender).collect::<Vec<_>>();
+        assert_eq!(genders(SaveData::parse(&content).unwrap()), genders(SaveData::parse(&content).unwrap()));
+    }
+}
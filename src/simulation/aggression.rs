@@ -0,0 +1,163 @@
+//! Territorial aggression.
+//!
+//! Aggressive species chase the tank mates they don't get along with, and
+//! when they catch up they nip. Nips cost health, and long fins make an
+//! easy target that can go on to rot.
+
+use rand::Rng;
+
+use crate::models::{Disease, FinShape, Fish, FishState, GrowthStage, Infection};
+
+use super::SimEvent;
+
+/// How close a target has to be before it gets chased
+const CHASE_RADIUS: f32 = 0.2;
+
+/// Close enough to nip
+const NIP_RADIUS: f32 = 0.05;
+
+/// Speed of a chase and of the escape, in tank widths per real second
+const CHASE_SPEED: f32 = 0.02;
+const FLEE_SPEED: f32 = 0.012;
+
+/// Nips per game hour at full aggression while in reach
+const NIP_RATE: f32 = 30.0;
+
+/// Health a nip takes off
+const NIP_DAMAGE: f32 = 5.0;
+
+/// Chase, flee and nip for one step of `dt` real seconds (`hours` game hours)
+pub fn update(fish: &mut [Fish], dt: f32, hours: f32, rng: &mut impl Rng, events: &mut Vec<SimEvent>) {
+    for attacker in 0..fish.len() {
+        let bully = &fish[attacker];
+        if !bully.alive || bully.state == FishState::Resting || bully.stage == GrowthStage::Fry {
+            continue;
+        }
+        let aggression = bully.species.def().behavior.aggression * bully.genome.temperament.value() * 2.0;
+        if aggression <= 0.0 {
+            continue;
+        }
+
+        // Nearest fish it has a problem with
+        let (x, y) = bully.position;
+        let target = fish
+            .iter()
+            .enumerate()
+            .filter(|&(i, f)| i != attacker && f.alive && bully.species.harasses(&f.species))
+            .map(|(i, f)| (i, ((f.position.0 - x).powi(2) + (f.position.1 - y).powi(2)).sqrt()))
+            .filter(|&(_, dist)| dist < CHASE_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((victim, dist)) = target else { continue };
+
+        // Bully closes in, victim bolts the other way
+        let (tx, ty) = fish[victim].position;
+        let (dx, dy) = if dist > 0.0 { ((tx - x) / dist, (ty - y) / dist) } else { (1.0, 0.0) };
+        let blend = (aggression * dt * 2.0).min(1.0);
+        steer(&mut fish[attacker], (dx * CHASE_SPEED, dy * CHASE_SPEED), blend);
+        steer(&mut fish[victim], (dx * FLEE_SPEED, dy * FLEE_SPEED), blend);
+
+        if dist < NIP_RADIUS && rng.gen_bool(((NIP_RATE * aggression * hours) as f64).clamp(0.0, 1.0)) {
+            let victim = &mut fish[victim];
+            let long_fins = victim.genome.fin_shape() != FinShape::Standard;
+            victim.injure(if long_fins { NIP_DAMAGE * 2.0 } else { NIP_DAMAGE });
+            victim.happiness = (victim.happiness - 10.0).max(0.0);
+
+            // Torn fins are an open door for fin rot
+            if victim.alive && victim.infection.is_none() && rng.gen_bool(0.1) {
+                victim.infection = Some(Infection::new(Disease::FinRot));
+                events.push(SimEvent::FishFellIll {
+                    name: victim.name.clone(),
+                    species: victim.species.clone(),
+                    disease: Disease::FinRot,
+                });
+            }
+        }
+    }
+}
+
+/// Blend a fish's velocity toward `target` by `amount` (0.0 - 1.0)
+fn steer(fish: &mut Fish, target: (f32, f32), amount: f32) {
+    fish.velocity.0 += (target.0 - fish.velocity.0) * amount;
+    fish.velocity.1 += (target.1 - fish.velocity.1) * amount;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Gene, Species};
+    use crate::simulation::SimRng;
+
+    /// Adult of `species` at `position`, as aggressive as its genes allow
+    fn adult(species: &str, position: (f32, f32), rng: &mut SimRng) -> Fish {
+        let mut fish = Fish::new(Species::new(species), species.to_string(), rng);
+        fish.stage = GrowthStage::Adult;
+        fish.genome.temperament = Gene(1.0, 1.0);
+        fish.genome.fins = (FinShape::Standard, FinShape::Standard);
+        fish.position = position;
+        fish.velocity = (0.0, 0.0);
+        fish
+    }
+
+    /// One step of an hour, long enough that every nip in reach lands
+    fn hour(fish: &mut [Fish], rng: &mut SimRng) -> Vec<SimEvent> {
+        let mut events = Vec::new();
+        update(fish, 0.1, 1.0, rng, &mut events);
+        events
+    }
+
+    #[test]
+    fn compatibility_goes_both_ways() {
+        let betta = Species::new("Betta");
+        let guppy = Species::new("Guppy");
+        let neon = Species::new("NeonTetra");
+        assert!(!betta.compatible_with(&guppy));
+        assert!(!guppy.compatible_with(&betta));
+        assert!(!betta.compatible_with(&betta), "territorial");
+        assert!(guppy.compatible_with(&neon));
+        assert!(guppy.compatible_with(&guppy));
+    }
+
+    #[test]
+    fn bully_chases_and_nips_in_reach() {
+        let mut rng = SimRng::new(1);
+        let mut fish = vec![adult("Betta", (0.5, 0.5), &mut rng), adult("Guppy", (0.52, 0.5), &mut rng)];
+        hour(&mut fish, &mut rng);
+        assert!(fish[0].velocity.0 > 0.0, "bully closes in");
+        assert!(fish[1].velocity.0 > 0.0, "victim flees");
+        assert_eq!(fish[1].health, 100.0 - NIP_DAMAGE);
+        assert_eq!(fish[0].health, 100.0);
+    }
+
+    #[test]
+    fn long_fins_take_worse_nips() {
+        let mut rng = SimRng::new(2);
+        let mut fish = vec![adult("Betta", (0.5, 0.5), &mut rng), adult("Guppy", (0.52, 0.5), &mut rng)];
+        fish[1].genome.fins = (FinShape::Veil, FinShape::Veil);
+        hour(&mut fish, &mut rng);
+        assert_eq!(fish[1].health, 100.0 - NIP_DAMAGE * 2.0);
+    }
+
+    #[test]
+    fn out_of_reach_or_peaceful_fish_leave_others_alone() {
+        let mut rng = SimRng::new(3);
+        let mut far = vec![adult("Betta", (0.1, 0.5), &mut rng), adult("Guppy", (0.9, 0.5), &mut rng)];
+        hour(&mut far, &mut rng);
+        assert!(far.iter().all(|f| f.health == 100.0 && f.velocity == (0.0, 0.0)));
+
+        let mut peaceful = vec![adult("Guppy", (0.5, 0.5), &mut rng), adult("NeonTetra", (0.51, 0.5), &mut rng)];
+        hour(&mut peaceful, &mut rng);
+        assert!(peaceful.iter().all(|f| f.health == 100.0));
+    }
+
+    #[test]
+    fn fry_and_sleeping_fish_do_not_bully() {
+        let mut rng = SimRng::new(4);
+        let mut fish = vec![adult("Betta", (0.5, 0.5), &mut rng), adult("Guppy", (0.52, 0.5), &mut rng)];
+        fish[0].stage = GrowthStage::Fry;
+        hour(&mut fish, &mut rng);
+        fish[0].stage = GrowthStage::Adult;
+        fish[0].state = FishState::Resting;
+        hour(&mut fish, &mut rng);
+        assert_eq!(fish[1].health, 100.0);
+    }
+}
@@ -0,0 +1,103 @@
+//! Algae on the glass.
+//!
+//! Algae grows under light, the brighter the faster, faster with nutrients (nitrate, dirty water) and
+//! slower when live plants compete with it. It feeds on nitrate, and once the
+//! glass is thickly coated the dying layers foul the water.
+
+use crate::models::{Equipment, EquipmentKind};
+use crate::persistence::WaterParams;
+
+/// Growth per game hour of full light with average nutrients
+const BASE_GROWTH: f32 = 0.6;
+
+/// Spores drifting in keep a clean tank from staying clean forever
+const SPORE_GROWTH: f32 = 0.05;
+
+/// Plants take most of the nutrients algae would use
+const PLANT_COMPETITION: f32 = 0.4;
+
+/// Nitrate (ppm) consumed per point of algae grown
+const NITRATE_USE: f32 = 0.05;
+
+/// Above this level the oldest algae dies off faster than it grows
+const DIE_OFF_LEVEL: f32 = 50.0;
+
+/// Purity lost per game hour at full coverage from dying algae
+const DIE_OFF_FOULING: f32 = 1.0;
+
+/// Grow (or foul the water with) algae for `hours` game hours
+pub fn update_algae(algae: &mut f32, water: &mut WaterParams, hours: f32, equipment: &Equipment, light: f32) {
+    if light > 0.0 {
+        // 1.0 at ~10 ppm nitrate and 80% purity, up to 3x in a neglected tank
+        let nutrients = (0.5 + water.nitrate / 20.0 + (100.0 - water.purity) / 100.0).clamp(0.2, 3.0);
+        let competition = if equipment.has(EquipmentKind::Plants) { PLANT_COMPETITION } else { 1.0 };
+        let room = 1.0 - *algae / 100.0;
+
+        let growth = (BASE_GROWTH * light * nutrients * competition * room + SPORE_GROWTH) * hours;
+        *algae = (*algae + growth).min(100.0);
+        water.nitrate = (water.nitrate - growth * NITRATE_USE).max(0.0);
+    }
+
+    if *algae > DIE_OFF_LEVEL {
+        let excess = (*algae - DIE_OFF_LEVEL) / (100.0 - DIE_OFF_LEVEL);
+        water.purity = (water.purity - excess * DIE_OFF_FOULING * hours).max(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{EquipmentItem, EquipmentModel};
+
+    /// Algae after `hours` game hours from a clean glass
+    fn grown(hours: usize, nitrate: f32, equipment: &Equipment, light: f32) -> f32 {
+        let mut algae = 0.0;
+        let mut water = WaterParams { nitrate, ..WaterParams::default() };
+        for _ in 0..hours {
+            update_algae(&mut algae, &mut water, 1.0, equipment, light);
+        }
+        algae
+    }
+
+    #[test]
+    fn no_growth_in_the_dark() {
+        assert_eq!(grown(48, 20.0, &Equipment::default(), 0.0), 0.0);
+    }
+
+    #[test]
+    fn brighter_light_and_more_nitrate_grow_more() {
+        let none = Equipment::default();
+        assert!(grown(24, 10.0, &none, 1.0) > grown(24, 10.0, &none, 0.3));
+        assert!(grown(24, 40.0, &none, 1.0) > grown(24, 0.0, &none, 1.0));
+    }
+
+    #[test]
+    fn plants_hold_algae_back() {
+        let plants = Equipment { items: vec![EquipmentItem::new(EquipmentModel::LivePlants)] };
+        assert!(grown(24, 10.0, &plants, 1.0) < grown(24, 10.0, &Equipment::default(), 1.0));
+    }
+
+    #[test]
+    fn growth_uses_nitrate_and_stops_at_full_cover() {
+        let mut algae = 0.0;
+        let mut water = WaterParams { nitrate: 20.0, ..WaterParams::default() };
+        update_algae(&mut algae, &mut water, 1.0, &Equipment::default(), 1.0);
+        assert!(water.nitrate < 20.0);
+
+        update_algae(&mut algae, &mut water, 10_000.0, &Equipment::default(), 1.0);
+        assert_eq!(algae, 100.0);
+    }
+
+    #[test]
+    fn thick_algae_fouls_the_water() {
+        let mut algae = 90.0;
+        let mut water = WaterParams::default();
+        update_algae(&mut algae, &mut water, 1.0, &Equipment::default(), 0.0);
+        assert!(water.purity < 100.0);
+
+        let mut algae = DIE_OFF_LEVEL;
+        let mut water = WaterParams::default();
+        update_algae(&mut algae, &mut water, 1.0, &Equipment::default(), 0.0);
+        assert_eq!(water.purity, 100.0);
+    }
+}
@@ -0,0 +1,271 @@
+//! Water chemistry: the nitrogen cycle and pH.
+//!
+//! Fish waste and rotting food become ammonia, a nitrifying bacteria colony
+//! turns ammonia into nitrite and nitrite into nitrate, and plants and water
+//! changes take the nitrate back out. pH is pulled toward a target set by the
+//! substrate and pushed around by respiration, nitrate and photosynthesis.
+
+use crate::models::{Equipment, EquipmentKind, Substrate};
+use crate::persistence::WaterParams;
+
+/// Colony strength bacteria can reach on the substrate alone, without a filter
+const SUBSTRATE_COLONY_CAP: f32 = 20.0;
+
+/// Share per game hour of the colony beyond what the running filters can
+/// host that dies back, as when the filter is taken out or loses power
+const STARVED_DIE_OFF: f32 = 0.1;
+
+/// Logistic growth rate of the colony per game hour while it has food
+const COLONY_GROWTH: f32 = 0.08;
+
+/// Share of the colony lost per game hour when there is nothing to eat
+const COLONY_STARVATION: f32 = 0.01;
+
+/// Share of ammonia a full colony converts to nitrite per game hour
+const AMMONIA_CONVERSION: f32 = 1.5;
+
+/// Share of nitrite a full colony converts to nitrate per game hour
+const NITRITE_CONVERSION: f32 = 1.5;
+
+/// Nitrite-eating bacteria only establish once the colony is past this point,
+/// which gives young tanks their nitrite spike
+const NITRITE_COLONY_START: f32 = 25.0;
+
+/// Share of waste that rots per game hour
+const WASTE_DECAY: f32 = 0.2;
+
+/// Ammonia (ppm) released per unit of rotted waste
+const WASTE_AMMONIA: f32 = 0.005;
+
+/// Nitrate (ppm) plants take up per game hour under full light. They only
+/// grow while lit, so twelve hours a day averages half this.
+const PLANT_UPTAKE: f32 = 0.2;
+
+/// Share of dissolved compounds removed by one water change
+pub const WATER_CHANGE_FRACTION: f32 = 0.3;
+
+/// pH of the water used for water changes
+const TAP_WATER_PH: f32 = 7.2;
+
+/// Dissolved oxygen (mg/L) of the water used for water changes
+const TAP_WATER_OXYGEN: f32 = 8.0;
+
+/// pH drop from the CO2 of a fully stocked tank (10 adult fish)
+const BIOLOAD_ACIDITY: f32 = 0.4;
+
+/// Reference fish waste (ppm per hour) for a fully stocked tank
+const FULL_BIOLOAD: f32 = 0.2;
+
+/// pH drop per ppm of nitrate (nitrification eats the water's buffer)
+const NITRATE_ACIDITY: f32 = 0.015;
+
+/// pH rise from plants using up CO2 under full light, and drop from them respiring in the dark
+const PLANT_DAY_PH: f32 = 0.3;
+const PLANT_NIGHT_PH: f32 = -0.1;
+
+/// How fast pH moves toward its target per game hour, before substrate buffering
+const PH_DRIFT_RATE: f32 = 0.05;
+
+/// Advance the nitrogen cycle by `hours` game hours.
+/// `fish_waste` is the ammonia (ppm per game hour) excreted by all live fish.
+pub fn update_nitrogen(water: &mut WaterParams, hours: f32, fish_waste: f32, equipment: &Equipment, light: f32) {
+    // Sources
+    let rotted = water.waste * (WASTE_DECAY * hours).min(1.0);
+    water.waste -= rotted;
+    water.ammonia += fish_waste * hours + rotted * WASTE_AMMONIA;
+
+    // Colony. Most of it lives in the filter media, so it can't outgrow the
+    // substrate without a running one, and starves back down over a few hours
+    let cap = equipment.colony_cap().unwrap_or(SUBSTRATE_COLONY_CAP);
+    if water.bacteria > cap {
+        water.bacteria -= (water.bacteria - cap) * (STARVED_DIE_OFF * hours).min(1.0);
+    }
+    if water.ammonia + water.nitrite <= 0.01 {
+        water.bacteria -= water.bacteria * (COLONY_STARVATION * hours).min(1.0);
+    } else if water.bacteria < cap {
+        // A trace colony is always around to seed growth
+        let colony = water.bacteria.max(1.0);
+        let growth = COLONY_GROWTH * colony * (1.0 - colony / cap) * hours;
+        water.bacteria = (colony + growth).min(cap);
+    }
+
+    // Nitrification
+    let ammonia_rate = water.bacteria / 100.0 * AMMONIA_CONVERSION;
+    let converted = water.ammonia * (ammonia_rate * hours).min(1.0);
+    water.ammonia -= converted;
+    water.nitrite += converted;
+
+    let nitrite_colony = ((water.bacteria - NITRITE_COLONY_START) / (100.0 - NITRITE_COLONY_START)).max(0.0);
+    let converted = water.nitrite * (nitrite_colony * NITRITE_CONVERSION * hours).min(1.0);
+    water.nitrite -= converted;
+    water.nitrate += converted;
+
+    if equipment.has(EquipmentKind::Plants) {
+        water.nitrate = (water.nitrate - PLANT_UPTAKE * light * hours).max(0.0);
+    }
+}
+
+/// Advance pH by `hours` game hours.
+/// `fish_waste` is the same bioload figure `update_nitrogen` takes.
+pub fn update_ph(
+    water: &mut WaterParams,
+    hours: f32,
+    fish_waste: f32,
+    equipment: &Equipment,
+    substrate: Substrate,
+    light: f32,
+) {
+    let mut target = substrate.base_ph();
+    target -= BIOLOAD_ACIDITY * (fish_waste / FULL_BIOLOAD);
+    target -= NITRATE_ACIDITY * water.nitrate;
+    if equipment.has(EquipmentKind::Plants) {
+        target += PLANT_NIGHT_PH + (PLANT_DAY_PH - PLANT_NIGHT_PH) * light;
+    }
+
+    // A buffering substrate keeps pulling back toward its own pH
+    let buffered = target + (substrate.base_ph() - target) * substrate.buffering();
+    let rate = (PH_DRIFT_RATE * (1.0 + substrate.buffering()) * hours).min(1.0);
+    water.ph = (water.ph + (buffered - water.ph) * rate).clamp(4.0, 10.0);
+}
+
+/// Dilute dissolved compounds and siphon out waste with a partial water change
+pub fn water_change(water: &mut WaterParams) {
+    let keep = 1.0 - WATER_CHANGE_FRACTION;
+    water.ammonia *= keep;
+    water.nitrite *= keep;
+    water.nitrate *= keep;
+    water.waste *= keep;
+    water.ph = water.ph * keep + TAP_WATER_PH * WATER_CHANGE_FRACTION;
+    water.oxygen = water.oxygen * keep + TAP_WATER_OXYGEN * WATER_CHANGE_FRACTION;
+}
+
+/// Whether a water change would achieve anything
+pub fn is_pristine(water: &WaterParams) -> bool {
+    water.purity >= 100.0
+        && water.ammonia < 0.01
+        && water.nitrite < 0.01
+        && water.nitrate < 1.0
+        && water.waste < 1.0
+        && (water.ph - TAP_WATER_PH).abs() < 0.1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{EquipmentItem, EquipmentModel};
+
+    fn with(models: &[EquipmentModel]) -> Equipment {
+        Equipment { items: models.iter().map(|&model| EquipmentItem::new(model)).collect() }
+    }
+
+    /// Run the nitrogen cycle for `hours` game hours in one-hour steps
+    fn run(water: &mut WaterParams, hours: usize, fish_waste: f32, equipment: &Equipment, light: f32) {
+        for _ in 0..hours {
+            update_nitrogen(water, 1.0, fish_waste, equipment, light);
+        }
+    }
+
+    #[test]
+    fn new_tank_cycles_through_ammonia_and_nitrite_to_nitrate() {
+        let filter = with(&[EquipmentModel::HangOnFilter]);
+        let mut water = WaterParams { ammonia: 2.0, ..WaterParams::default() };
+        run(&mut water, 24, 0.05, &filter, 0.0);
+        assert!(water.bacteria > 0.0);
+        assert!(water.nitrite > 0.0, "a young colony lets nitrite build up");
+
+        run(&mut water, 24 * 14, 0.05, &filter, 0.0);
+        assert!(water.bacteria > NITRITE_COLONY_START && water.bacteria <= 100.0);
+        assert!(water.ammonia < 0.1);
+        assert!(water.nitrite < 0.1);
+        assert!(water.nitrate > 1.0);
+    }
+
+    #[test]
+    fn rotting_waste_becomes_ammonia() {
+        let mut water = WaterParams { waste: 50.0, ..WaterParams::default() };
+        update_nitrogen(&mut water, 1.0, 0.0, &Equipment::default(), 0.0);
+        assert!(water.waste < 50.0);
+        assert!(water.ammonia > 0.0);
+    }
+
+    #[test]
+    fn colony_dies_back_without_a_filter() {
+        let mut water = WaterParams { bacteria: 100.0, ammonia: 0.5, ..WaterParams::default() };
+        run(&mut water, 48, 0.05, &Equipment::default(), 0.0);
+        assert!(water.bacteria < 30.0);
+        assert!(water.bacteria >= SUBSTRATE_COLONY_CAP);
+    }
+
+    #[test]
+    fn colony_starves_without_ammonia() {
+        let filter = with(&[EquipmentModel::HangOnFilter]);
+        let mut water = WaterParams { bacteria: 80.0, ..WaterParams::default() };
+        run(&mut water, 24, 0.0, &filter, 0.0);
+        assert!(water.bacteria < 80.0 && water.bacteria > 0.0);
+    }
+
+    #[test]
+    fn plants_take_up_nitrate_only_under_light() {
+        let plants = with(&[EquipmentModel::LivePlants]);
+        let mut lit = WaterParams { nitrate: 10.0, ..WaterParams::default() };
+        let mut dark = lit.clone();
+        update_nitrogen(&mut lit, 1.0, 0.0, &plants, 1.0);
+        update_nitrogen(&mut dark, 1.0, 0.0, &plants, 0.0);
+        assert!(lit.nitrate < 10.0);
+        assert_eq!(dark.nitrate, 10.0);
+    }
+
+    #[test]
+    fn water_change_dilutes_compounds() {
+        let mut water = WaterParams { ammonia: 1.0, nitrite: 1.0, nitrate: 40.0, waste: 10.0, ..WaterParams::default() };
+        water_change(&mut water);
+        let keep = 1.0 - WATER_CHANGE_FRACTION;
+        assert_eq!(water.ammonia, keep);
+        assert_eq!(water.nitrite, keep);
+        assert_eq!(water.nitrate, 40.0 * keep);
+        assert_eq!(water.waste, 10.0 * keep);
+        assert!(!is_pristine(&water));
+    }
+
+    /// pH after `hours` game hours of drift from 7.0
+    fn ph_after(hours: usize, fish_waste: f32, nitrate: f32, equipment: &Equipment, substrate: Substrate, light: f32) -> f32 {
+        let mut water = WaterParams { nitrate, ..WaterParams::default() };
+        for _ in 0..hours {
+            update_ph(&mut water, 1.0, fish_waste, equipment, substrate, light);
+        }
+        water.ph
+    }
+
+    #[test]
+    fn ph_settles_toward_the_substrate() {
+        let none = Equipment::default();
+        let coral = ph_after(200, 0.0, 0.0, &none, Substrate::CrushedCoral, 0.0);
+        let soil = ph_after(200, 0.0, 0.0, &none, Substrate::AquaSoil, 0.0);
+        assert!(coral > 7.5 && coral <= Substrate::CrushedCoral.base_ph());
+        assert!(soil < 6.8 && soil >= Substrate::AquaSoil.base_ph());
+    }
+
+    #[test]
+    fn bioload_and_nitrate_acidify() {
+        let none = Equipment::default();
+        let clean = ph_after(48, 0.0, 0.0, &none, Substrate::Gravel, 0.0);
+        assert!(ph_after(48, FULL_BIOLOAD, 0.0, &none, Substrate::Gravel, 0.0) < clean);
+        assert!(ph_after(48, 0.0, 40.0, &none, Substrate::Gravel, 0.0) < clean);
+    }
+
+    #[test]
+    fn plants_lift_ph_in_light_and_lower_it_in_the_dark() {
+        let none = Equipment::default();
+        let plants = with(&[EquipmentModel::LivePlants]);
+        let bare = ph_after(48, 0.0, 0.0, &none, Substrate::Gravel, 1.0);
+        assert!(ph_after(48, 0.0, 0.0, &plants, Substrate::Gravel, 1.0) > bare);
+        assert!(ph_after(48, 0.0, 0.0, &plants, Substrate::Gravel, 0.0) < bare);
+    }
+
+    #[test]
+    fn ph_stays_in_bounds() {
+        let mut water = WaterParams { nitrate: 10_000.0, ..WaterParams::default() };
+        update_ph(&mut water, 1000.0, 100.0, &Equipment::default(), Substrate::AquaSoil, 0.0);
+        assert_eq!(water.ph, 4.0);
+    }
+}
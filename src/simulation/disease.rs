@@ -0,0 +1,218 @@
+//! Disease outbreaks and medication.
+//!
+//! Stressed fish and poor water make fish fall ill, contagious diseases jump
+//! between tank mates, and a dose of the right medication cures them at the
+//! cost of a side effect on the water.
+
+use rand::Rng;
+
+use crate::models::{Disease, Fish, Infection, Medication, Treatment};
+use crate::persistence::WaterParams;
+
+use super::SimEvent;
+
+/// Stress below which fish fight off infections on their own
+const STRESS_THRESHOLD: f32 = 0.3;
+
+/// Severity lost per game hour while the right medication is in the water
+const TREATMENT_RATE: f32 = 8.0;
+
+/// Chance per game hour that `fish` comes down with `disease`
+fn onset_chance(fish: &Fish, disease: Disease, water: &WaterParams) -> f32 {
+    let stress = (fish.stress() - STRESS_THRESHOLD).max(0.0);
+    let profile = fish.species.profile();
+    match disease {
+        Disease::Ich => {
+            let (temp_min, temp_max) = profile.temperature;
+            let wrong_temp = water.temperature < temp_min || water.temperature > temp_max;
+            0.02 * stress * if wrong_temp { 3.0 } else { 1.0 }
+        }
+        Disease::FinRot => {
+            let dirty = water.purity < 50.0 || water.nitrate > 40.0 || water.ammonia > 0.25;
+            0.02 * stress + if dirty { 0.01 } else { 0.0 }
+        }
+        Disease::SwimBladder => {
+            let overfed = fish.hunger > 90.0;
+            let cold = water.temperature < profile.temperature.0;
+            if overfed || cold { 0.004 + 0.01 * stress } else { 0.0 }
+        }
+    }
+}
+
+/// Infect, spread and progress diseases for one step of `hours` game hours
+pub fn update_diseases(
+    fish: &mut [Fish],
+    water: &WaterParams,
+    treatment: Option<&Treatment>,
+    hours: f32,
+    rng: &mut impl Rng,
+    events: &mut Vec<SimEvent>,
+) {
+    let protected = |disease: Disease| treatment.is_some_and(|t| t.medication.treats(disease));
+
+    // How contagious the tank is for each disease, from everyone already sick
+    let exposure = |disease: Disease| -> f32 {
+        fish.iter()
+            .filter_map(|f| f.infection.filter(|i| f.alive && i.disease == disease))
+            .map(|i| disease.contagion() * i.severity / 100.0)
+            .sum()
+    };
+    let exposures: Vec<(Disease, f32)> = Disease::ALL.iter().map(|&d| (d, exposure(d))).collect();
+
+    for fish in fish.iter_mut().filter(|f| f.alive) {
+        let stress = fish.stress();
+        match fish.infection.as_mut() {
+            Some(infection) => {
+                let disease = infection.disease;
+                let change = if protected(disease) {
+                    -TREATMENT_RATE
+                } else {
+                    // Calm fish slowly shake it off, stressed ones get worse
+                    disease.progression() * (stress * 2.0 - 0.3)
+                };
+                infection.severity = (infection.severity + change * hours).min(100.0);
+                if infection.severity <= 0.0 {
+                    fish.infection = None;
+                    events.push(SimEvent::FishRecovered {
+                        name: fish.name.clone(),
+                        species: fish.species.clone(),
+                        disease,
+                    });
+                }
+            }
+            None => {
+                for &(disease, exposure) in &exposures {
+                    if protected(disease) {
+                        continue;
+                    }
+                    let chance = onset_chance(fish, disease, water) + exposure;
+                    if rng.gen_bool(((chance * hours) as f64).clamp(0.0, 1.0)) {
+                        fish.infection = Some(Infection::new(disease));
+                        events.push(SimEvent::FishFellIll {
+                            name: fish.name.clone(),
+                            species: fish.species.clone(),
+                            disease,
+                        });
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Side effects of the medication in the water; ends the course once used up
+pub fn update_treatment(treatment: &mut Option<Treatment>, water: &mut WaterParams, fish: &mut [Fish], hours: f32) {
+    let Some(course) = treatment.as_mut() else { return };
+
+    match course.medication {
+        Medication::IchTreatment => {
+            water.bacteria = (water.bacteria - 1.0 * hours).max(0.0);
+            for fish in fish.iter_mut().filter(|f| f.alive) {
+                fish.happiness = (fish.happiness - 1.0 * hours).max(0.0);
+            }
+        }
+        Medication::Antibiotic => {
+            water.bacteria = (water.bacteria - 3.0 * hours).max(0.0);
+        }
+        Medication::EpsomSalt => {
+            if water.ph < 8.2 {
+                water.ph = (water.ph + 0.05 * hours).min(8.2);
+            }
+        }
+    }
+
+    course.hours_left -= hours;
+    if course.hours_left <= 0.0 {
+        *treatment = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Species;
+    use crate::simulation::SimRng;
+
+    /// Content, well-fed fish in water that suits them
+    fn calm_tank(count: usize, rng: &mut SimRng) -> (Vec<Fish>, WaterParams) {
+        let fish: Vec<Fish> = (0..count)
+            .map(|i| {
+                let mut fish = Fish::new(Species::new("Guppy"), format!("Fish {i}"), rng);
+                fish.happiness = 100.0;
+                fish.hunger = 60.0;
+                fish
+            })
+            .collect();
+        let (low, high) = fish[0].species.profile().temperature;
+        let water = WaterParams { temperature: (low + high) / 2.0, ..WaterParams::default() };
+        (fish, water)
+    }
+
+    /// Run `hours` one-hour steps, returning everything that happened
+    fn run(fish: &mut [Fish], water: &WaterParams, treatment: Option<&Treatment>, hours: usize, rng: &mut SimRng) -> Vec<SimEvent> {
+        let mut events = Vec::new();
+        for _ in 0..hours {
+            update_diseases(fish, water, treatment, 1.0, rng, &mut events);
+        }
+        events
+    }
+
+    fn sick(fish: &[Fish], disease: Disease) -> usize {
+        fish.iter().filter(|f| f.infection.is_some_and(|i| i.disease == disease)).count()
+    }
+
+    #[test]
+    fn calm_fish_in_clean_water_stay_healthy() {
+        let mut rng = SimRng::new(1);
+        let (mut fish, water) = calm_tank(6, &mut rng);
+        assert!(run(&mut fish, &water, None, 200, &mut rng).is_empty());
+    }
+
+    #[test]
+    fn ich_spreads_to_tank_mates() {
+        let mut rng = SimRng::new(2);
+        let (mut fish, water) = calm_tank(6, &mut rng);
+        fish[0].infection = Some(Infection { disease: Disease::Ich, severity: 100.0 });
+        let events = run(&mut fish, &water, None, 48, &mut rng);
+        assert!(sick(&fish[1..], Disease::Ich) > 0);
+        assert!(events.iter().any(|e| matches!(e, SimEvent::FishFellIll { disease: Disease::Ich, .. })));
+    }
+
+    #[test]
+    fn medication_cures_and_protects() {
+        let mut rng = SimRng::new(3);
+        let (mut fish, water) = calm_tank(6, &mut rng);
+        fish[0].infection = Some(Infection { disease: Disease::Ich, severity: 100.0 });
+        let treatment = Treatment::new(Medication::IchTreatment);
+        let events = run(&mut fish, &water, Some(&treatment), 48, &mut rng);
+        assert_eq!(sick(&fish, Disease::Ich), 0);
+        assert!(events.iter().any(|e| matches!(e, SimEvent::FishRecovered { disease: Disease::Ich, .. })));
+        assert!(!events.iter().any(|e| matches!(e, SimEvent::FishFellIll { .. })));
+    }
+
+    #[test]
+    fn wrong_medication_does_not_cure() {
+        let mut rng = SimRng::new(4);
+        let (mut fish, water) = calm_tank(1, &mut rng);
+        fish[0].happiness = 0.0; // Too stressed to shake it off
+        fish[0].infection = Some(Infection::new(Disease::FinRot));
+        let treatment = Treatment::new(Medication::EpsomSalt);
+        run(&mut fish, &water, Some(&treatment), 10, &mut rng);
+        assert!(fish[0].infection.is_some_and(|i| i.severity > Infection::new(Disease::FinRot).severity));
+    }
+
+    #[test]
+    fn medication_side_effects_wear_off_with_the_course() {
+        let mut rng = SimRng::new(5);
+        let (mut fish, mut water) = calm_tank(1, &mut rng);
+        water.bacteria = 100.0;
+        let mut treatment = Some(Treatment::new(Medication::Antibiotic));
+        update_treatment(&mut treatment, &mut water, &mut fish, 1.0);
+        assert!(water.bacteria < 100.0);
+
+        update_treatment(&mut treatment, &mut water, &mut fish, Medication::Antibiotic.course_hours());
+        assert!(treatment.is_none());
+        assert!(water.bacteria >= 0.0);
+    }
+}
@@ -0,0 +1,96 @@
+//! Equipment wear.
+//!
+//! Filters clog with the muck they pull out of the water, faster in a
+//! crowded tank, and a clogged filter barely moves any water. Heaters age
+//! slowly. Servicing puts a piece back to as good as new, but rinsing filter
+//! media also washes away some of the bacteria living in it.
+
+use crate::models::{Equipment, EquipmentItem, EquipmentKind};
+use crate::persistence::WaterParams;
+
+/// Share of the bacteria colony rinsed out with the filter media
+const RINSE_BACTERIA_LOSS: f32 = 0.2;
+
+/// Wear everything in the tank for `hours` game hours at `stocking`. With
+/// the `power_out`, only what runs without it wears.
+pub fn wear(equipment: &mut Equipment, stocking: f32, hours: f32, power_out: bool) {
+    for item in equipment.items.iter_mut().filter(|item| !(power_out && item.model.kind().powered())) {
+        // Filters clog with what the fish put out
+        let load = if item.model.kind() == EquipmentKind::Filter { 0.5 + stocking } else { 1.0 };
+        item.wear = (item.wear + item.model.wear_rate() * load * hours).min(100.0);
+    }
+}
+
+/// Clean or service `item`. Returns false if there was nothing to do.
+pub fn service(item: &mut EquipmentItem, water: &mut WaterParams) -> bool {
+    if item.wear <= 0.0 {
+        return false;
+    }
+    if item.model.kind() == EquipmentKind::Filter {
+        water.bacteria *= 1.0 - RINSE_BACTERIA_LOSS;
+    }
+    item.wear = 0.0;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::EquipmentModel;
+
+    fn with(models: &[EquipmentModel]) -> Equipment {
+        Equipment { items: models.iter().map(|&model| EquipmentItem::new(model)).collect() }
+    }
+
+    #[test]
+    fn filters_clog_faster_in_a_crowded_tank() {
+        let mut light = with(&[EquipmentModel::HangOnFilter]);
+        let mut crowded = light.clone();
+        wear(&mut light, 0.2, 10.0, false);
+        wear(&mut crowded, 1.5, 10.0, false);
+        assert!(light.items[0].wear > 0.0);
+        assert!(crowded.items[0].wear > light.items[0].wear);
+
+        wear(&mut crowded, 1.5, 10_000.0, false);
+        assert_eq!(crowded.items[0].wear, 100.0);
+    }
+
+    #[test]
+    fn worn_filters_clean_less() {
+        let mut equipment = with(&[EquipmentModel::CanisterFilter]);
+        let fresh = equipment.filtration();
+        wear(&mut equipment, 1.0, 10_000.0, false);
+        assert!(equipment.filtration() < fresh);
+        assert!(equipment.filtration() > 0.0);
+    }
+
+    #[test]
+    fn plants_and_lids_never_wear() {
+        let mut equipment = with(&[EquipmentModel::LivePlants, EquipmentModel::Lid]);
+        wear(&mut equipment, 1.0, 1000.0, false);
+        assert!(equipment.items.iter().all(|item| item.wear == 0.0));
+    }
+
+    #[test]
+    fn nothing_powered_wears_in_a_power_cut() {
+        let mut equipment = with(&[EquipmentModel::SpongeFilter, EquipmentModel::Heater50W, EquipmentModel::AirStone]);
+        wear(&mut equipment, 1.0, 100.0, true);
+        assert!(equipment.items.iter().all(|item| item.wear == 0.0));
+    }
+
+    #[test]
+    fn servicing_a_filter_rinses_out_some_bacteria() {
+        let mut water = WaterParams { bacteria: 100.0, ..WaterParams::default() };
+        let mut filter = EquipmentItem::new(EquipmentModel::HangOnFilter);
+        assert!(!service(&mut filter, &mut water), "nothing to clean yet");
+
+        filter.wear = 80.0;
+        assert!(service(&mut filter, &mut water));
+        assert_eq!(filter.wear, 0.0);
+        assert_eq!(water.bacteria, 100.0 * (1.0 - RINSE_BACTERIA_LOSS));
+
+        let mut heater = EquipmentItem { wear: 50.0, ..EquipmentItem::new(EquipmentModel::Heater100W) };
+        assert!(service(&mut heater, &mut water));
+        assert_eq!(water.bacteria, 100.0 * (1.0 - RINSE_BACTERIA_LOSS));
+    }
+}
@@ -0,0 +1,91 @@
+//! Feeding.
+//!
+//! Food dropped in at the surface sinks toward the substrate. Hungry fish
+//! swim over and eat whatever they reach, and anything left lying on the
+//! bottom rots into waste that fouls the water.
+
+use crate::models::food::SUBSTRATE_DEPTH;
+use crate::models::{Fish, FishState, FoodParticle};
+use crate::persistence::WaterParams;
+
+/// How fast flakes sink, in tank heights per real second
+const SINK_SPEED: f32 = 0.03;
+
+/// Fish go looking for food below this hunger, but eat anything in reach
+const HUNGRY: f32 = 80.0;
+
+/// How far away a fish notices food
+const SIGHT_RADIUS: f32 = 0.5;
+
+/// Close enough to eat
+const EAT_RADIUS: f32 = 0.05;
+
+/// Speed of a fish heading for food, in tank widths per real second
+const SEEK_SPEED: f32 = 0.02;
+
+/// Hunger points a fish can eat per real second
+const BITE_RATE: f32 = 5.0;
+
+/// Hunger points of food on the substrate that rot into waste per game hour
+const ROT_RATE: f32 = 5.0;
+
+/// Purity lost for every point of food that rots
+const ROT_PURITY: f32 = 0.05;
+
+/// Let food sink for `dt` real seconds
+pub fn sink(food: &mut [FoodParticle], dt: f32) {
+    for particle in food.iter_mut() {
+        particle.prev_position = Some(particle.position);
+        particle.position.1 = (particle.position.1 + SINK_SPEED * particle.kind.sink_speed() * dt).min(SUBSTRATE_DEPTH);
+    }
+}
+
+/// Hungry fish head for the nearest food and eat what they reach
+pub fn update(fish: &mut [Fish], food: &mut Vec<FoodParticle>, dt: f32) {
+    for fish in fish.iter_mut() {
+        if !fish.alive || fish.state == FishState::Resting {
+            continue; // Sleeping fish miss the meal
+        }
+        if fish.state == FishState::Eating {
+            fish.state = FishState::Swimming; // Back to swimming unless it takes another bite
+        }
+
+        let (x, y) = fish.position;
+        let nearest = food
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.nutrition > 0.0)
+            .map(|(i, p)| (i, ((p.position.0 - x).powi(2) + (p.position.1 - y).powi(2)).sqrt()))
+            .filter(|&(_, dist)| dist < SIGHT_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((i, dist)) = nearest else { continue };
+
+        if dist < EAT_RADIUS {
+            // Greedy: a fish keeps eating past full if food is under its nose
+            if fish.hunger < 100.0 {
+                let eaten = fish.eat((BITE_RATE * dt).min(food[i].nutrition), food[i].kind);
+                food[i].nutrition -= eaten;
+            }
+        } else if fish.hunger < HUNGRY {
+            let (tx, ty) = food[i].position;
+            let (dx, dy) = ((tx - x) / dist, (ty - y) / dist);
+            let blend = (dt * 2.0).min(1.0);
+            fish.velocity.0 += (dx * SEEK_SPEED - fish.velocity.0) * blend;
+            fish.velocity.1 += (dy * SEEK_SPEED - fish.velocity.1) * blend;
+        }
+    }
+    food.retain(|p| p.nutrition > 0.0);
+}
+
+/// Rot uneaten food on the substrate for `hours` game hours. `swing` is how
+/// much faster the tank's concentrations change than in 100 L.
+pub fn rot(food: &mut Vec<FoodParticle>, water: &mut WaterParams, hours: f32, swing: f32) {
+    for particle in food.iter_mut().filter(|p| p.settled()) {
+        let rotted = (ROT_RATE * hours).min(particle.nutrition);
+        particle.nutrition -= rotted;
+        let pollution = rotted * particle.kind.pollution() * swing;
+        water.waste += pollution;
+        water.purity = (water.purity - pollution * ROT_PURITY).max(0.0);
+    }
+    food.retain(|p| p.nutrition > 0.0);
+}
@@ -0,0 +1,288 @@
+//! Random incidents.
+//!
+//! Now and then something goes wrong: a heater's thermostat sticks, a filter
+//! clogs, the power goes out, a heatwave rolls in or a fish jumps out of a
+//! tank with no lid. Each one lasts until the player deals with it or it
+//! runs its course, so leaving the tanks alone for long is a gamble. Only
+//! fish jumping out wait for the player: nobody is around to startle them
+//! while the game is closed, and one already on the floor hangs on until
+//! the player is back to put it in.
+
+use rand::Rng;
+
+use crate::models::{DeathCause, EquipmentItem, EquipmentKind, Fish, FishState, Incident, IncidentKind, LineageRecord};
+use crate::persistence::{SaveData, Tank};
+
+use super::{equipment, SimEvent, SimRng};
+
+/// Chance per game hour, per heater, of its thermostat sticking
+const HEATER_STUCK_CHANCE: f32 = 0.002;
+
+/// Chance per game hour, per filter, of it clogging solid
+const FILTER_CLOG_CHANCE: f32 = 0.003;
+
+/// Chance per game hour of a power cut, and how long one lasts
+const OUTAGE_CHANCE: f32 = 0.001;
+const OUTAGE_HOURS: (f32, f32) = (1.0, 6.0);
+
+/// Chance per game hour of a heatwave, and how long one lasts
+const HEATWAVE_CHANCE: f32 = 0.0005;
+const HEATWAVE_HOURS: (f32, f32) = (24.0, 72.0);
+
+/// How much warmer the room gets in a heatwave, and how much of that still
+/// reaches a tank with a fan and ice packs on it
+const HEATWAVE_WARMING: f32 = 6.0;
+const COOLED_WARMING: f32 = 2.0;
+
+/// Water temperature a stuck heater drives toward
+pub const STUCK_HEATER_TEMP: f32 = 34.0;
+
+/// Chance per game hour, per fish, of a calm fish jumping out of an open
+/// tank. Stressed fish are up to four times as jumpy.
+const JUMP_CHANCE: f32 = 0.0005;
+
+/// Game hours a fish survives on the floor
+const STRANDED_HOURS: f32 = 0.5;
+
+/// Health a fish loses from its time on the floor
+const STRANDED_DAMAGE: f32 = 20.0;
+
+fn happens(rng: &mut SimRng, chance: f32, hours: f32) -> bool {
+    rng.gen_bool(((chance * hours) as f64).clamp(0.0, 1.0))
+}
+
+/// Whether `kind` is going on in the tank
+pub fn active(incidents: &[Incident], kind: IncidentKind) -> bool {
+    incidents.iter().any(|incident| incident.kind() == kind)
+}
+
+/// Power cuts and heatwaves hit the whole house. Returns any that start
+/// during the next `hours` game hours.
+pub fn roll_household(rng: &mut SimRng, hours: f32) -> Vec<Incident> {
+    let mut started = Vec::new();
+    if happens(rng, OUTAGE_CHANCE, hours) {
+        started.push(Incident::PowerOutage { hours_left: rng.gen_range(OUTAGE_HOURS.0..OUTAGE_HOURS.1) });
+    }
+    if happens(rng, HEATWAVE_CHANCE, hours) {
+        started.push(Incident::Heatwave {
+            hours_left: rng.gen_range(HEATWAVE_HOURS.0..HEATWAVE_HOURS.1),
+            cooled: false,
+        });
+    }
+    started
+}
+
+/// How much a heatwave is warming the room around the tank, if there is one
+pub fn warming(incidents: &[Incident]) -> f32 {
+    incidents
+        .iter()
+        .find_map(|incident| match incident {
+            Incident::Heatwave { cooled: false, .. } => Some(HEATWAVE_WARMING),
+            Incident::Heatwave { cooled: true, .. } => Some(COOLED_WARMING),
+            _ => None,
+        })
+        .unwrap_or(0.0)
+}
+
+/// Fish lying on the floor next to the tank
+pub fn stranded(tank: &Tank) -> impl Iterator<Item = &Fish> {
+    tank.incidents.iter().filter_map(|incident| match incident {
+        Incident::JumpedOut { fish, .. } => Some(&**fish),
+        _ => None,
+    })
+}
+
+/// Start `incident` in the tank unless the same thing is already going on
+pub fn start(tank: &mut Tank, incident: Incident, events: &mut Vec<SimEvent>) {
+    let kind = incident.kind();
+    if kind != IncidentKind::JumpedOut && active(&tank.incidents, kind) {
+        return;
+    }
+    events.push(SimEvent::IncidentStarted { tank: tank.name.clone(), kind });
+    tank.incidents.push(incident);
+}
+
+/// Roll for equipment failures and jumpers in one tank over `hours` game
+/// hours. Fish don't jump while the player is `away`.
+pub fn roll(tank: &mut Tank, rng: &mut SimRng, hours: f32, away: bool, events: &mut Vec<SimEvent>) {
+    if !active(&tank.incidents, IncidentKind::PowerOutage) {
+        let heaters = tank.equipment.items.iter().filter(|item| item.model.kind() == EquipmentKind::Heater).count();
+        if (0..heaters).any(|_| happens(rng, HEATER_STUCK_CHANCE, hours)) {
+            start(tank, Incident::HeaterStuck, events);
+        }
+
+        let mut clogged = false;
+        for filter in tank.equipment.items.iter_mut().filter(|item| item.model.kind() == EquipmentKind::Filter) {
+            if happens(rng, FILTER_CLOG_CHANCE, hours) {
+                filter.wear = 100.0;
+                clogged = true;
+            }
+        }
+        if clogged {
+            start(tank, Incident::FilterClogged, events);
+        }
+    }
+
+    // Without a lid, a spooked fish can clear the rim
+    if away || tank.equipment.has(EquipmentKind::Lid) {
+        return;
+    }
+    let mut index = 0;
+    while index < tank.fish.len() {
+        let fish = &tank.fish[index];
+        if fish.alive && happens(rng, JUMP_CHANCE * (1.0 + 3.0 * fish.stress()), hours) {
+            let fish = Box::new(tank.fish.remove(index));
+            start(tank, Incident::JumpedOut { fish, hours_left: STRANDED_HOURS }, events);
+        } else {
+            index += 1;
+        }
+    }
+}
+
+/// Run incidents down by `hours` game hours, ending those that are over or
+/// were dealt with some other way. Stranded fish hold on while the player
+/// is `away`. Returns fish that died on the floor.
+pub fn update(tank: &mut Tank, hours: f32, away: bool, events: &mut Vec<SimEvent>) -> Vec<Fish> {
+    let has_heater = tank.equipment.has(EquipmentKind::Heater);
+    let filter_clogged = tank.equipment.items.iter().any(|item| {
+        item.model.kind() == EquipmentKind::Filter && item.needs_service()
+    });
+
+    let mut dead = Vec::new();
+    let mut index = 0;
+    while index < tank.incidents.len() {
+        let over = match &mut tank.incidents[index] {
+            Incident::HeaterStuck => !has_heater, // Taken out of the tank
+            Incident::FilterClogged => !filter_clogged, // Cleaned from the equipment menu
+            Incident::PowerOutage { hours_left } | Incident::Heatwave { hours_left, .. } => {
+                *hours_left -= hours;
+                *hours_left <= 0.0
+            }
+            Incident::JumpedOut { .. } if away => false,
+            Incident::JumpedOut { hours_left, .. } => {
+                *hours_left -= hours;
+                *hours_left <= 0.0
+            }
+        };
+        if !over {
+            index += 1;
+            continue;
+        }
+
+        let incident = tank.incidents.remove(index);
+        match incident {
+            Incident::JumpedOut { mut fish, .. } => {
+                fish.alive = false;
+                fish.health = 0.0;
+                fish.state = FishState::Dead;
+                fish.cause_of_death = Some(DeathCause::JumpedOut);
+                dead.push(*fish);
+            }
+            other => events.push(SimEvent::IncidentOver { tank: tank.name.clone(), kind: other.kind() }),
+        }
+    }
+    dead
+}
+
+/// Deal with the oldest incident in the tank on screen that still needs
+/// it. Returns what was dealt with, or `None` if all is well.
+pub fn respond(save: &mut SaveData, events: &mut Vec<SimEvent>) -> Option<IncidentKind> {
+    let SaveData { tanks, active_tank, storage, ancestors, total_time, .. } = save;
+    let tank = &mut tanks[*active_tank];
+    let index = tank.incidents.iter().position(Incident::needs_response)?;
+    match tank.incidents.remove(index) {
+        Incident::HeaterStuck => {
+            // Every heater comes out, since there's no telling which one stuck
+            let (heaters, rest): (Vec<EquipmentItem>, Vec<EquipmentItem>) = std::mem::take(&mut tank.equipment.items)
+                .into_iter()
+                .partition(|item| item.model.kind() == EquipmentKind::Heater);
+            tank.equipment.items = rest;
+            storage.extend(heaters);
+            Some(IncidentKind::HeaterStuck)
+        }
+        Incident::PowerOutage { .. } => {
+            // One battery backup for the whole house
+            for tank in tanks.iter_mut() {
+                tank.incidents.retain(|incident| incident.kind() != IncidentKind::PowerOutage);
+            }
+            Some(IncidentKind::PowerOutage)
+        }
+        Incident::Heatwave { hours_left, .. } => {
+            tank.incidents.insert(index, Incident::Heatwave { hours_left, cooled: true });
+            Some(IncidentKind::Heatwave)
+        }
+        Incident::FilterClogged => {
+            let Tank { equipment, water, .. } = tank;
+            for filter in equipment.items.iter_mut().filter(|item| item.model.kind() == EquipmentKind::Filter) {
+                if filter.needs_service() {
+                    equipment::service(filter, water);
+                }
+            }
+            Some(IncidentKind::FilterClogged)
+        }
+        Incident::JumpedOut { mut fish, .. } => {
+            fish.injure(STRANDED_DAMAGE);
+            if !fish.alive {
+                // Too weak to survive the time on the floor
+                fish.cause_of_death = Some(DeathCause::JumpedOut);
+                events.push(SimEvent::FishDied {
+                    name: fish.name.clone(),
+                    species: fish.species.clone(),
+                    cause: fish.cause_of_death,
+                });
+                ancestors.push(LineageRecord::of(&fish, Some(*total_time)));
+            }
+            fish.position.1 = 0.1; // Dropped back in at the top
+            fish.prev_position = None;
+            tank.fish.push(*fish);
+            Some(IncidentKind::JumpedOut)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Species;
+
+    /// Save with one weak fish lying on the floor of the tank on screen
+    fn stranded_save(health: f32) -> SaveData {
+        let mut save = SaveData::with_seed(1);
+        let mut fish = Fish::new(Species::new("Guppy"), "Jumper".to_string(), &mut save.rng);
+        fish.health = health;
+        save.tank_mut().incidents.push(Incident::JumpedOut { fish: Box::new(fish), hours_left: STRANDED_HOURS });
+        save
+    }
+
+    #[test]
+    fn rescued_fish_goes_back_in() {
+        let mut save = stranded_save(100.0);
+        let mut events = Vec::new();
+        assert_eq!(respond(&mut save, &mut events), Some(IncidentKind::JumpedOut));
+        let fish = &save.tank().fish[0];
+        assert!(fish.alive);
+        assert_eq!(fish.health, 100.0 - STRANDED_DAMAGE);
+        assert!(events.is_empty());
+        assert!(save.ancestors.is_empty());
+    }
+
+    #[test]
+    fn fish_too_weak_to_rescue_is_recorded_dead() {
+        let mut save = stranded_save(STRANDED_DAMAGE / 2.0);
+        let mut events = Vec::new();
+        respond(&mut save, &mut events);
+        let fish = &save.tank().fish[0];
+        assert!(!fish.alive);
+        assert_eq!(fish.cause_of_death, Some(DeathCause::JumpedOut));
+        assert!(matches!(events[..], [SimEvent::FishDied { cause: Some(DeathCause::JumpedOut), .. }]));
+        assert_eq!(save.ancestors.len(), 1);
+        assert_eq!(save.ancestors[0].id, fish.id);
+    }
+
+    #[test]
+    fn stranded_fish_are_found() {
+        let save = stranded_save(100.0);
+        assert_eq!(stranded(save.tank()).count(), 1);
+        assert!(save.tank().fish.is_empty());
+    }
+}
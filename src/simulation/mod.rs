@@ -0,0 +1,1046 @@
+use rand::Rng;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::models::{light::DARK, DeathCause, Decoration, DecorationType, Disease, Equipment, EquipmentItem, EquipmentKind, EquipmentModel, Fish, FoodParticle, FoodType, IncidentKind, Light, LightSetting, LineageRecord, Medication, Species, Substrate, Surroundings, TankSize, Treatment};
+use crate::persistence::{SaveData, Tank, WaterParams};
+
+pub mod aggression;
+pub mod algae;
+pub mod chemistry;
+pub mod disease;
+pub mod equipment;
+pub mod feeding;
+pub mod incidents;
+pub mod oxygen;
+pub mod rng;
+pub mod schooling;
+pub mod stocking;
+
+pub use rng::SimRng;
+
+/// Game seconds that pass for every real second while the game is running
+pub const TIME_SCALE: f64 = 3.0;
+
+/// Length of one simulation step in game seconds (one frame at ~30 FPS)
+pub const FIXED_STEP: f64 = 0.1;
+
+/// Most steps `advance` will run in one call, so a stalled frame can't
+/// snowball into ever longer catch-up work
+pub const MAX_STEPS_PER_ADVANCE: usize = 30;
+
+/// Backlog beyond this many game seconds is dropped instead of caught up
+const MAX_BACKLOG: f64 = 30.0;
+
+/// Rounding slack when the backlog holds a whole number of steps, so 3.0
+/// game seconds run as 30 steps and not 29 plus a remainder
+const STEP_EPSILON: f64 = 1e-9;
+
+/// Most tanks a save can hold; every one of them is simulated all the time
+pub const MAX_TANKS: usize = 6;
+
+/// Pieces of food dropped in per fish when feeding
+const PIECES_PER_FISH: usize = 3;
+
+/// Offline time beyond this is ignored (24 hours)
+const MAX_OFFLINE_SECONDS: f64 = 24.0 * 3600.0;
+
+/// Game seconds simulated per real second while the game is closed
+const OFFLINE_TIME_SCALE: f64 = 1.0;
+
+/// Real seconds of absence replayed per offline step
+const OFFLINE_CHUNK: f64 = 60.0;
+
+/// Longest slice of game time fish move, eat and fight in. Online steps are
+/// shorter; offline steps are split into slices this long.
+const MAX_MOVE_STEP: f64 = 1.0;
+
+/// Something that happened during a step that a front end may want to report
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimEvent {
+    FishBorn { name: String, species: Species },
+    FishDied { name: String, species: Species, cause: Option<DeathCause> },
+    FishFellIll { name: String, species: Species, disease: Disease },
+    FishRecovered { name: String, species: Species, disease: Disease },
+    IncidentStarted { tank: String, kind: IncidentKind },
+    IncidentOver { tank: String, kind: IncidentKind }, // Ran its course or sorted itself out
+}
+
+/// Lowest and highest value a parameter reached over a period
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParamRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl ParamRange {
+    fn new(value: f32) -> Self {
+        Self { min: value, max: value }
+    }
+
+    fn include(&mut self, value: f32) {
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+}
+
+/// Water parameter extremes seen while away
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaterRanges {
+    pub purity: ParamRange,
+    pub ph: ParamRange,
+    pub temperature: ParamRange,
+    pub ammonia: ParamRange,
+    pub nitrite: ParamRange,
+    pub nitrate: ParamRange,
+    pub oxygen: ParamRange,
+}
+
+impl WaterRanges {
+    fn new(water: &WaterParams) -> Self {
+        Self {
+            purity: ParamRange::new(water.purity),
+            ph: ParamRange::new(water.ph),
+            temperature: ParamRange::new(water.temperature),
+            ammonia: ParamRange::new(water.ammonia),
+            nitrite: ParamRange::new(water.nitrite),
+            nitrate: ParamRange::new(water.nitrate),
+            oxygen: ParamRange::new(water.oxygen),
+        }
+    }
+
+    fn include(&mut self, water: &WaterParams) {
+        self.purity.include(water.purity);
+        self.ph.include(water.ph);
+        self.temperature.include(water.temperature);
+        self.ammonia.include(water.ammonia);
+        self.nitrite.include(water.nitrite);
+        self.nitrate.include(water.nitrate);
+        self.oxygen.include(water.oxygen);
+    }
+}
+
+/// A fish's stats before and after the absence
+#[derive(Debug, Clone, PartialEq)]
+pub struct FishChange {
+    pub id: Uuid,
+    pub name: String,
+    pub species: Species,
+    pub alive: bool,
+    pub hunger: (f32, f32),
+    pub happiness: (f32, f32),
+    pub health: (f32, f32),
+    pub energy: (f32, f32),
+}
+
+/// How one tank fared while the game was closed
+#[derive(Debug, Clone)]
+pub struct TankReport {
+    pub name: String,
+    pub water: WaterRanges,
+    pub equipment: Equipment, // What was installed while away
+    pub fish: Vec<FishChange>, // Fish that were in the tank when the game closed
+}
+
+/// What happened to the tanks while the game was closed
+#[derive(Debug, Clone)]
+pub struct OfflineReport {
+    pub elapsed_seconds: f64,   // Real seconds since the last save
+    pub clock_seconds: f64,     // How far the game clock moved
+    pub simulated_seconds: f64, // Game time fish and water lived through, up to the offline cap
+    pub events: Vec<SimEvent>,
+    pub tanks: Vec<TankReport>,
+}
+
+impl OfflineReport {
+    /// Whether the absence ran past the offline cap, so only part of it was
+    /// played out
+    pub fn capped(&self) -> bool {
+        self.elapsed_seconds > MAX_OFFLINE_SECONDS
+    }
+
+    pub fn births(&self) -> impl Iterator<Item = &SimEvent> {
+        self.events.iter().filter(|e| matches!(e, SimEvent::FishBorn { .. }))
+    }
+
+    pub fn deaths(&self) -> impl Iterator<Item = &SimEvent> {
+        self.events.iter().filter(|e| matches!(e, SimEvent::FishDied { .. }))
+    }
+
+    /// Fish that fell ill or recovered
+    pub fn illnesses(&self) -> impl Iterator<Item = &SimEvent> {
+        self.events
+            .iter()
+            .filter(|e| matches!(e, SimEvent::FishFellIll { .. } | SimEvent::FishRecovered { .. }))
+    }
+
+    /// Things that went wrong, and things that came right again
+    pub fn incidents(&self) -> impl Iterator<Item = &SimEvent> {
+        self.events
+            .iter()
+            .filter(|e| matches!(e, SimEvent::IncidentStarted { .. } | SimEvent::IncidentOver { .. }))
+    }
+}
+
+/// Headless tank simulation.
+///
+/// Owns the save data and advances water, fish, breeding and the game clock
+/// for every tank in it. Front ends call `advance` with elapsed game time and
+/// `drain_events` afterwards; the world itself only ever moves in
+/// `FIXED_STEP` increments. Player actions apply to the active tank.
+pub struct Simulation {
+    pub save_data: SaveData,
+    events: Vec<SimEvent>,
+    accumulator: f64, // Game seconds not yet simulated
+    away: bool,       // Replaying the time the game was closed
+}
+
+impl Simulation {
+    pub fn new(save_data: SaveData) -> Self {
+        Self {
+            save_data,
+            events: Vec::new(),
+            accumulator: 0.0,
+            away: false,
+        }
+    }
+
+    /// Run `real_seconds` of wall time with the game open. Frames go through
+    /// `advance`; a gap longer than it would catch up on (the machine slept
+    /// with the game open, say) is replayed like time away instead of being
+    /// dropped, and reported the same way.
+    pub fn run_for(&mut self, real_seconds: f64) -> Option<OfflineReport> {
+        let game_delta = real_seconds.max(0.0) * TIME_SCALE;
+        if self.accumulator + game_delta > MAX_BACKLOG {
+            return self.apply_offline_progress(real_seconds);
+        }
+        self.advance(game_delta);
+        None
+    }
+
+    /// Queue `game_delta` game seconds and run as many fixed steps as fit.
+    ///
+    /// At most `MAX_STEPS_PER_ADVANCE` steps run per call; the remainder is
+    /// carried to the next call, up to `MAX_BACKLOG`, and anything beyond
+    /// that is dropped. Returns the number of steps taken.
+    pub fn advance(&mut self, game_delta: f64) -> usize {
+        if self.save_data.is_frozen {
+            self.accumulator = 0.0;
+            return 0;
+        }
+
+        self.accumulator = (self.accumulator + game_delta.max(0.0)).min(MAX_BACKLOG);
+
+        let mut steps = 0;
+        while self.accumulator + STEP_EPSILON >= FIXED_STEP && steps < MAX_STEPS_PER_ADVANCE {
+            self.step(FIXED_STEP);
+            self.accumulator = (self.accumulator - FIXED_STEP).max(0.0);
+            steps += 1;
+        }
+        steps
+    }
+
+    /// How far (0.0-1.0) the clock is between the last step and the next one.
+    /// Front ends blend positions with this for smooth movement.
+    pub fn interpolation_alpha(&self) -> f32 {
+        (self.accumulator / FIXED_STEP).clamp(0.0, 1.0) as f32
+    }
+
+    /// Apply the time the game was closed for.
+    ///
+    /// The absence is replayed through the same step logic as online play, in
+    /// `OFFLINE_CHUNK` slices, against each tank's real water and equipment.
+    /// Returns a report when fish were in any tank, `None` if the world is
+    /// frozen or too little time has passed to matter. Events from a replay
+    /// without a report are left for `drain_events`.
+    pub fn apply_offline_progress(&mut self, elapsed_seconds: f64) -> Option<OfflineReport> {
+        if self.save_data.is_frozen {
+            return None;
+        }
+
+        // Cap offline time to prevent excessive decay
+        let capped_seconds = elapsed_seconds.clamp(0.0, MAX_OFFLINE_SECONDS);
+        let before: Vec<Vec<Fish>> = self.save_data.tanks.iter().map(|tank| tank.fish.clone()).collect();
+        let queued_events = self.events.len();
+        let mut water: Vec<WaterRanges> = self.save_data.tanks.iter().map(|tank| WaterRanges::new(&tank.water)).collect();
+
+        // Offline progression is slower to be fair:
+        // - Online: 1 real sec = 3 game sec
+        // - Offline: 1 real sec = 1 game sec (slower decay)
+        // The clock still runs at full speed to keep the day/night cycle continuous.
+        self.away = true;
+        let mut remaining = capped_seconds;
+        while remaining > 0.0 {
+            let chunk = remaining.min(OFFLINE_CHUNK);
+            self.step_with_clock(chunk * OFFLINE_TIME_SCALE, chunk * TIME_SCALE);
+            for (ranges, tank) in water.iter_mut().zip(&self.save_data.tanks) {
+                ranges.include(&tank.water);
+            }
+            remaining -= chunk;
+        }
+        self.away = false;
+
+        // Time past the cap only moves the clock
+        self.save_data.total_time += (elapsed_seconds - capped_seconds).max(0.0) * TIME_SCALE;
+
+        // Without a report, what happened stays queued for `drain_events`
+        if before.iter().all(|fish| fish.is_empty()) || capped_seconds <= 60.0 {
+            return None;
+        }
+
+        // Everything that happened while away is reported through the report
+        let events: Vec<SimEvent> = self.events.drain(queued_events..).collect();
+
+        let tanks = self
+            .save_data
+            .tanks
+            .iter()
+            .zip(before)
+            .zip(water)
+            .map(|((tank, before), water)| TankReport {
+                name: tank.name.clone(),
+                water,
+                equipment: tank.equipment.clone(),
+                fish: fish_changes(&before, &tank.fish),
+            })
+            .collect();
+
+        Some(OfflineReport {
+            elapsed_seconds,
+            clock_seconds: elapsed_seconds.max(0.0) * TIME_SCALE,
+            simulated_seconds: capped_seconds * OFFLINE_TIME_SCALE,
+            events,
+            tanks,
+        })
+    }
+
+    /// Advance the simulation by exactly `game_delta` game seconds.
+    /// Prefer `advance`, which splits time into fixed steps.
+    pub fn step(&mut self, game_delta: f64) {
+        self.step_with_clock(game_delta, game_delta);
+    }
+
+    /// Simulate `game_delta` game seconds of fish and water while moving the
+    /// day/night clock by `clock_delta`. They only differ offline.
+    fn step_with_clock(&mut self, game_delta: f64, clock_delta: f64) {
+        if self.save_data.is_frozen {
+            return;
+        }
+
+        self.save_data.total_time += clock_delta;
+
+        let (hour, _) = self.game_time();
+        let is_night = self.is_night();
+
+        // Power cuts and heatwaves hit every tank in the house
+        let household = incidents::roll_household(&mut self.save_data.rng, (game_delta / 3600.0) as f32);
+        for incident in household {
+            for tank in &mut self.save_data.tanks {
+                incidents::start(tank, incident.clone(), &mut self.events);
+            }
+        }
+
+        // Tanks don't affect each other; each gets the same slice of time
+        for index in 0..self.save_data.tanks.len() {
+            self.step_tank(index, game_delta, hour, is_night);
+        }
+    }
+
+    fn step_tank(&mut self, index: usize, game_delta: f64, hour: u8, is_night: bool) {
+        let SaveData { tanks, ancestors, rng, total_time, .. } = &mut self.save_data;
+        let tank = &mut tanks[index];
+
+        // The tank's own light, not the sun, sets its day
+        let light = tank.light.level(hour, !is_night);
+        update_water(tank, game_delta, is_night, light);
+
+        // 1. Basic Update & Movement (Iterate all)
+        let hours = (game_delta / 3600.0) as f32;
+        let surroundings = surroundings_of(tank);
+        let Tank { size, fish, food, water, treatment, .. } = &mut *tank;
+        let was_alive: Vec<bool> = fish.iter().map(|f| f.alive).collect();
+        for fish in fish.iter_mut() {
+            fish.update_for_time_of_day(light < DARK);
+        }
+
+        // Swimming, feeding and fights only behave with short steps, so a
+        // long (offline) step is played out in `MAX_MOVE_STEP` slices.
+        // Movement speeds are tuned in real seconds.
+        let moves = (game_delta / MAX_MOVE_STEP).ceil().max(1.0);
+        let move_delta = game_delta / moves;
+        let real_delta = move_delta / TIME_SCALE;
+        let move_hours = (move_delta / 3600.0) as f32;
+        for _ in 0..moves as usize {
+            schooling::steer(fish, real_delta as f32);
+            aggression::update(fish, real_delta as f32, move_hours, rng, &mut self.events);
+            feeding::sink(food, real_delta as f32);
+            feeding::update(fish, food, real_delta as f32);
+            for fish in fish.iter_mut() {
+                fish.update_position(real_delta, rng);
+            }
+        }
+
+        // Stats and water take the whole step at once
+        feeding::rot(food, water, hours, size.swing());
+        for (fish, was_alive) in fish.iter_mut().zip(was_alive) {
+            fish.update(game_delta, water, &surroundings);
+
+            if was_alive && !fish.alive {
+                self.events.push(SimEvent::FishDied {
+                    name: fish.name.clone(),
+                    species: fish.species.clone(),
+                    cause: fish.cause_of_death,
+                });
+                ancestors.push(LineageRecord::of(fish, Some(*total_time)));
+            }
+        }
+
+        // 2. Illness and medication
+        disease::update_treatment(treatment, water, fish, hours);
+        disease::update_diseases(fish, water, treatment.as_ref(), hours, rng, &mut self.events);
+
+        // 3. Breeding Pass
+        breed(tank, rng, *total_time, &mut self.events);
+
+        // 4. Mishaps
+        incidents::roll(tank, rng, hours, self.away, &mut self.events);
+        for fish in incidents::update(tank, hours, self.away, &mut self.events) {
+            self.events.push(SimEvent::FishDied {
+                name: fish.name.clone(),
+                species: fish.species.clone(),
+                cause: fish.cause_of_death,
+            });
+            ancestors.push(LineageRecord::of(&fish, Some(*total_time)));
+            tank.fish.push(fish);
+        }
+    }
+
+    /// Take all events produced since the last call
+    pub fn drain_events(&mut self) -> Vec<SimEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// The tank on screen
+    pub fn tank(&self) -> &Tank {
+        self.save_data.tank()
+    }
+
+    /// Set up a new, empty tank and switch to it. Returns its name, or
+    /// `None` if there are already `MAX_TANKS`.
+    pub fn add_tank(&mut self) -> Option<&str> {
+        let tanks = &mut self.save_data.tanks;
+        if tanks.len() >= MAX_TANKS {
+            return None;
+        }
+        tanks.push(Tank::new(Tank::default_name(tanks.len())));
+        self.save_data.active_tank = tanks.len() - 1;
+        Some(&self.tank().name)
+    }
+
+    /// Show the tank `step` places along, wrapping around. Returns its name.
+    pub fn switch_tank(&mut self, step: isize) -> &str {
+        let count = self.save_data.tanks.len() as isize;
+        self.save_data.active_tank = (self.save_data.active_tank as isize + step).rem_euclid(count) as usize;
+        &self.tank().name
+    }
+
+    /// Move living fish `id` from the tank on screen into tank `to`, keeping
+    /// it where it was swimming. Returns false if either can't be found or
+    /// the fish has died.
+    pub fn move_fish(&mut self, id: Uuid, to: usize) -> bool {
+        let from = self.save_data.active_tank;
+        if to == from || to >= self.save_data.tanks.len() {
+            return false;
+        }
+        let Some(index) = self.tank().fish.iter().position(|f| f.id == id && f.alive) else {
+            return false;
+        };
+        let mut fish = self.save_data.tanks[from].fish.remove(index);
+        fish.prev_position = None;
+        self.save_data.tanks[to].fish.push(fish);
+        true
+    }
+
+    /// Add a fish to the tank. Going past capacity is allowed; the water
+    /// pays for it.
+    pub fn add_fish(&mut self, mut fish: Fish) {
+        fish.born_at = self.save_data.total_time;
+        self.save_data.tank_mut().fish.push(fish);
+    }
+
+    /// Centimetres of adult fish the tank can hold with its current filtration
+    pub fn capacity(&self) -> f32 {
+        stocking::capacity(self.tank().size.litres(), &self.tank().equipment)
+    }
+
+    /// Bioload as a share of capacity; above 1.0 the tank is overstocked
+    pub fn stocking(&self) -> f32 {
+        stocking::level(self.tank())
+    }
+
+    /// Sprinkle `kind` of food on the surface for every living fish. Returns
+    /// how many fish it was meant for. Food nobody eats settles on the
+    /// substrate and rots.
+    pub fn drop_food(&mut self, kind: FoodType) -> usize {
+        let SaveData { tanks, active_tank, rng, .. } = &mut self.save_data;
+        let Tank { fish, food, .. } = &mut tanks[*active_tank];
+        let living = fish.iter().filter(|f| f.alive).count();
+        for _ in 0..living * PIECES_PER_FISH {
+            food.push(FoodParticle::drop(kind, rng));
+        }
+        living
+    }
+
+    /// Partial water change. Returns false if the water was already clean.
+    pub fn change_water(&mut self) -> bool {
+        let tank = self.save_data.tank_mut();
+        let water = &mut tank.water;
+        if chemistry::is_pristine(water) {
+            return false;
+        }
+
+        water.purity = (water.purity + 30.0).min(100.0);
+        chemistry::water_change(water);
+
+        // Fresh water dilutes any medication in the tank too
+        if let Some(treatment) = &mut tank.treatment {
+            treatment.hours_left *= 0.7;
+        }
+        true
+    }
+
+    /// Dose the medication for whichever illness is worst in the tank,
+    /// replacing any course already running. Returns `None` if nobody is sick.
+    pub fn medicate(&mut self) -> Option<Medication> {
+        let burden = |disease: Disease| -> f32 {
+            self.tank()
+                .fish
+                .iter()
+                .filter(|f| f.alive)
+                .filter_map(|f| f.infection.filter(|i| i.disease == disease))
+                .map(|i| i.severity)
+                .sum()
+        };
+        let worst = Disease::ALL
+            .into_iter()
+            .filter(|&d| burden(d) > 0.0)
+            .max_by(|&a, &b| burden(a).total_cmp(&burden(b)))?;
+
+        let medication = worst.cure();
+        self.save_data.tank_mut().treatment = Some(Treatment::new(medication));
+        Some(medication)
+    }
+
+    /// Scrub algae off the glass. Returns false if there was nothing to scrub.
+    pub fn scrub_algae(&mut self) -> bool {
+        let tank = self.save_data.tank_mut();
+        if tank.algae_level <= 0.0 {
+            return false;
+        }
+        tank.algae_level = (tank.algae_level - 20.0).max(0.0);
+        true
+    }
+
+    /// Place a random decoration on a free spot of the substrate.
+    /// Returns false if no free spot was found.
+    pub fn add_random_decoration(&mut self) -> bool {
+        let types = [DecorationType::Rock, DecorationType::Plant, DecorationType::Castle, DecorationType::Skull];
+        let SaveData { tanks, active_tank, rng, .. } = &mut self.save_data;
+        let decorations = &mut tanks[*active_tank].decorations;
+
+        // Try to find a non-overlapping spot (Max 10 attempts)
+        for _ in 0..10 {
+            let rand_type = types[rng.gen_range(0..types.len())];
+
+            // Random X, centered somewhat (0.1 to 0.9)
+            let x = rng.gen::<f32>().clamp(0.1, 0.9);
+
+            // Check overlap with existing decorations
+            // Heuristic: Assume width ~ 15% (0.15)
+            let width_allowance = 0.15;
+            let overlap = decorations.iter().any(|d| (d.position.0 - x).abs() < width_allowance);
+
+            if !overlap {
+                let deco = Decoration::new(rand_type, (x, 0.0), rng);
+                decorations.push(deco);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Move the tank's contents into the next size of tank. Returns the new
+    /// size. The water comes along, so its parameters don't change.
+    pub fn cycle_tank_size(&mut self) -> TankSize {
+        let tank = self.save_data.tank_mut();
+        tank.size = tank.size.next();
+        tank.size
+    }
+
+    /// Deal with the oldest incident in the tank on screen that still needs
+    /// it. Returns what was dealt with, or `None` if nothing is wrong.
+    pub fn respond_to_incident(&mut self) -> Option<IncidentKind> {
+        incidents::respond(&mut self.save_data, &mut self.events)
+    }
+
+    /// Install a brand new `model` in the tank
+    pub fn install_equipment(&mut self, model: EquipmentModel) {
+        self.save_data.tank_mut().equipment.items.push(EquipmentItem::new(model));
+    }
+
+    /// Install spare `index` from the cupboard in the tank. Returns false if
+    /// there is no such spare.
+    pub fn install_spare(&mut self, index: usize) -> bool {
+        if index >= self.save_data.storage.len() {
+            return false;
+        }
+        let item = self.save_data.storage.remove(index);
+        self.save_data.tank_mut().equipment.items.push(item);
+        true
+    }
+
+    /// Take item `index` out of the tank and put it in the cupboard, wear and
+    /// all. Returns false if there is no such item.
+    pub fn remove_equipment(&mut self, index: usize) -> bool {
+        let items = &mut self.save_data.tank_mut().equipment.items;
+        if index >= items.len() {
+            return false;
+        }
+        let item = items.remove(index);
+        self.save_data.storage.push(item);
+        true
+    }
+
+    /// Throw out spare `index`. Returns false if there is no such spare.
+    pub fn discard_spare(&mut self, index: usize) -> bool {
+        if index >= self.save_data.storage.len() {
+            return false;
+        }
+        self.save_data.storage.remove(index);
+        true
+    }
+
+    /// Clean or service item `index` in the tank. Returns false if it didn't
+    /// need it.
+    pub fn service_equipment(&mut self, index: usize) -> bool {
+        let Tank { equipment, water, .. } = self.save_data.tank_mut();
+        match equipment.items.get_mut(index) {
+            Some(item) => equipment::service(item, water),
+            None => false,
+        }
+    }
+
+    /// Slide item `index` across the tank by `step` (a share of its width)
+    pub fn move_equipment(&mut self, index: usize, step: f32) {
+        if let Some(item) = self.save_data.tank_mut().equipment.items.get_mut(index) {
+            item.position = (item.position + step).clamp(0.0, 1.0);
+        }
+    }
+
+    /// Swap the substrate for the next kind. Returns the new one.
+    pub fn cycle_substrate(&mut self) -> Substrate {
+        let tank = self.save_data.tank_mut();
+        tank.substrate = tank.substrate.next();
+        tank.substrate
+    }
+
+    /// Remove all fish and reset the water in the tank on screen. Equipment,
+    /// its settings and the family trees stay.
+    pub fn restart(&mut self) {
+        // Keep the removed fish in the family trees of their relatives
+        let SaveData { tanks, active_tank, ancestors, .. } = &mut self.save_data;
+        let tank = &mut tanks[*active_tank];
+        for fish in tank.fish.iter().chain(incidents::stranded(tank)) {
+            if !ancestors.iter().any(|record| record.id == fish.id) {
+                ancestors.push(LineageRecord::of(fish, None));
+            }
+        }
+        tank.fish.clear();
+        tank.food.clear();
+        tank.treatment = None;
+        tank.incidents.clear();
+        tank.water = WaterParams::default();
+    }
+
+    /// Species of living fish in the tank on screen that won't get along
+    /// with `species`
+    pub fn incompatible_with(&self, species: &Species) -> Vec<Species> {
+        self.incompatible_in(self.save_data.active_tank, species)
+    }
+
+    /// Species of living fish in tank `index` that won't get along with
+    /// `species`. Includes `species` itself for territorial fish that
+    /// already have company.
+    pub fn incompatible_in(&self, index: usize, species: &Species) -> Vec<Species> {
+        let mut clashes: Vec<Species> = Vec::new();
+        for fish in self.save_data.tanks[index].fish.iter().filter(|f| f.alive) {
+            if !species.compatible_with(&fish.species) && !clashes.contains(&fish.species) {
+                clashes.push(fish.species.clone());
+            }
+        }
+        clashes
+    }
+
+    /// Every fish in every tank, and any that jumped out and are waiting to
+    /// be put back
+    fn all_fish(&self) -> impl Iterator<Item = &Fish> {
+        self.save_data.tanks.iter().flat_map(|tank| tank.fish.iter().chain(incidents::stranded(tank)))
+    }
+
+    /// Family record of a fish, living or dead, in any tank
+    pub fn family_member(&self, id: Uuid) -> Option<LineageRecord> {
+        self.save_data
+            .ancestors
+            .iter()
+            .find(|record| record.id == id)
+            .cloned()
+            .or_else(|| {
+                let fish = self.all_fish().find(|f| f.id == id)?;
+                Some(LineageRecord::of(fish, None))
+            })
+    }
+
+    /// Every known child of a fish, oldest first
+    pub fn children_of(&self, id: Uuid) -> Vec<LineageRecord> {
+        let mut children: Vec<LineageRecord> = self
+            .save_data
+            .ancestors
+            .iter()
+            .filter(|record| record.is_child_of(id))
+            .cloned()
+            .collect();
+        for record in self.all_fish().map(|fish| LineageRecord::of(fish, None)) {
+            if record.is_child_of(id) && !children.iter().any(|c| c.id == record.id) {
+                children.push(record);
+            }
+        }
+        let now = self.save_data.total_time;
+        children.sort_by(|a, b| a.birth_clock(now).total_cmp(&b.birth_clock(now)));
+        children
+    }
+
+    /// Conditions in the tank on screen that fish react to besides the water
+    pub fn surroundings(&self) -> Surroundings {
+        surroundings_of(self.tank())
+    }
+
+    /// Current game time of day as (hour, minute)
+    pub fn game_time(&self) -> (u8, u8) {
+        let game_seconds = self.save_data.total_time.max(0.0) as i64;
+        let game_time = game_seconds % (24 * 3600); // 24-hour cycle
+
+        let hour = (game_time / 3600) as u8;
+        let minute = ((game_time % 3600) / 60) as u8;
+        (hour, minute)
+    }
+
+    /// Check if it's currently night time
+    pub fn is_night(&self) -> bool {
+        let (hour, _) = self.game_time();
+        !(6..18).contains(&hour)
+    }
+
+    /// Light (0.0 - 1.0) in the tank on screen right now
+    pub fn light_level(&self) -> f32 {
+        let (hour, _) = self.game_time();
+        self.tank().light.level(hour, !self.is_night())
+    }
+
+    /// Change a setting of the light over the tank on screen
+    pub fn adjust_light(&mut self, setting: LightSetting, step: i32) -> Light {
+        let light = &mut self.save_data.tank_mut().light;
+        light.adjust(setting, step);
+        *light
+    }
+}
+
+/// Stat changes of the fish in `before` that are still in `after`
+fn fish_changes(before: &[Fish], after: &[Fish]) -> Vec<FishChange> {
+    before
+        .iter()
+        .filter_map(|old| {
+            let new = after.iter().find(|f| f.id == old.id)?;
+            Some(FishChange {
+                id: old.id,
+                name: new.name.clone(),
+                species: new.species.clone(),
+                alive: new.alive,
+                hunger: (old.hunger, new.hunger),
+                happiness: (old.happiness, new.happiness),
+                health: (old.health, new.health),
+                energy: (old.energy, new.energy),
+            })
+        })
+        .collect()
+}
+
+/// Tank conditions fish react to besides the water
+fn surroundings_of(tank: &Tank) -> Surroundings {
+    let mut population = HashMap::new();
+    for fish in tank.fish.iter().filter(|f| f.alive) {
+        *population.entry(fish.species.clone()).or_insert(0) += 1;
+    }
+    Surroundings {
+        algae_level: tank.algae_level,
+        tank_litres: tank.size.litres(),
+        population,
+    }
+}
+
+/// `is_night` goes by the sun and sets the room temperature; `light` is
+/// what the tank's own light and the window let in (0.0 - 1.0)
+fn update_water(tank: &mut Tank, game_delta: f64, is_night: bool, light: f32) {
+    let hours = game_delta / 3600.0; // Use game hours for simulation
+
+    // Less water means everything fish put into it is more concentrated
+    let swing = tank.size.swing();
+
+    // Purity degrades over time (-1.0 per hour, faster with more and
+    // bigger fish: +0.5 for every 10 cm of adult fish in 100 L)
+    let mut degradation_rate = 1.0 + stocking::bioload(&tank.fish) * 0.05 * swing;
+    let stocking = stocking::level(tank);
+
+    // Filters and plants take out their share, less as filters clog. In a
+    // power cut only the plants keep going, and nothing else wears.
+    let power_out = incidents::active(&tank.incidents, IncidentKind::PowerOutage);
+    equipment::wear(&mut tank.equipment, stocking, hours as f32, power_out);
+    let running = if power_out {
+        Cow::Owned(tank.equipment.unpowered())
+    } else {
+        Cow::Borrowed(&tank.equipment)
+    };
+    degradation_rate *= 1.0 - running.filtration();
+
+    // An overstocked tank makes more mess than the filter can handle
+    degradation_rate += stocking::extra_degradation(stocking);
+
+    let water = &mut tank.water;
+    water.purity = (water.purity - (degradation_rate * hours as f32)).max(0.0);
+
+    // Temperature fluctuations (Warmer day, Cooler night, hot in a heatwave)
+    let mut target_temp = if is_night { 23.0 } else { 26.0 };
+    target_temp += incidents::warming(&tank.incidents);
+    let mut temp_diff = target_temp - water.temperature;
+
+    // Heaters stabilize temp (up to 80% less fluctuation with a watt per litre)
+    let heating = running.heating(tank.size.litres());
+    temp_diff *= 1.0 - 0.8 * heating;
+
+    // A heater stuck on keeps heating well past comfortable
+    if incidents::active(&tank.incidents, IncidentKind::HeaterStuck) {
+        temp_diff += (incidents::STUCK_HEATER_TEMP - water.temperature) * heating;
+    }
+
+    // A smaller body of water heats up and cools down faster
+    water.temperature += temp_diff * (0.5 * swing.sqrt() * hours as f32);
+
+    let fish_waste: f32 = tank.fish.iter().map(|f| f.waste_rate()).sum::<f32>() * (1.0 + stocking::overstock(stocking)) * swing;
+    chemistry::update_nitrogen(water, hours as f32, fish_waste, &running, light);
+    chemistry::update_ph(
+        water,
+        hours as f32,
+        fish_waste,
+        &running,
+        tank.substrate,
+        light,
+    );
+    algae::update_algae(
+        &mut tank.algae_level,
+        water,
+        hours as f32,
+        &running,
+        light,
+    );
+
+    // Stirred-up water takes in oxygen faster; in a power cut it goes still
+    oxygen::update_oxygen(
+        water,
+        hours as f32,
+        stocking::bioload(&tank.fish),
+        swing,
+        running.aeration(),
+        running.has(EquipmentKind::Plants),
+        light,
+    );
+}
+
+fn breed(tank: &mut Tank, rng: &mut SimRng, total_time: f64, events: &mut Vec<SimEvent>) {
+    // Fish don't spawn in a crowded tank
+    if stocking::level(tank) >= 1.0 {
+        return;
+    }
+
+    let mut new_fry = Vec::new();
+    let fish = &mut tank.fish;
+    let count = fish.len();
+    for i in 0..count {
+        for j in (i + 1)..count {
+            // Use split_at_mut to get two mutable references
+            let (left, right) = fish.split_at_mut(j);
+            // left[i] is first fish, right[0] is second fish
+            if let Some(fry) = left[i].try_breed(&mut right[0], rng) {
+                new_fry.push(fry);
+            }
+        }
+    }
+
+    for mut fry in new_fry {
+        if stocking::level(tank) < 1.0 {
+            fry.name = format!("Baby {}", tank.fish.len() + 1);
+            fry.born_at = total_time;
+            events.push(SimEvent::FishBorn {
+                name: fry.name.clone(),
+                species: fry.species.clone(),
+            });
+            tank.fish.push(fry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Gender, Incident, Infection};
+
+    /// Seeded simulation with a few fish and some food in the water
+    fn stocked(seed: u64) -> Simulation {
+        let mut sim = Simulation::new(SaveData::with_seed(seed));
+        for (index, species) in ["Guppy", "NeonTetra", "Betta"].into_iter().enumerate() {
+            let fish = Fish::new(Species::new(species), format!("Fish {index}"), &mut sim.save_data.rng);
+            sim.add_fish(fish);
+        }
+        sim.drop_food(FoodType::Flakes);
+        sim
+    }
+
+    /// Seeded simulation where fish eat, breed and pass on a disease
+    fn busy(seed: u64) -> Simulation {
+        let mut sim = stocked(seed);
+        sim.save_data.total_time = 12.0 * 3600.0; // Midday, with the light on
+        let SaveData { tanks, active_tank, rng, .. } = &mut sim.save_data;
+        let tank = &mut tanks[*active_tank];
+        for gender in [Gender::Female, Gender::Male] {
+            let mut fish = Fish::new(Species::new("Guppy"), format!("{gender:?}"), rng);
+            fish.gender = gender;
+            fish.age = chrono::Duration::hours(48);
+            fish.happiness = 100.0;
+            tank.fish.push(fish);
+        }
+        tank.fish[1].infection = Some(Infection::new(Disease::Ich));
+        for fish in tank.fish.iter_mut() {
+            fish.hunger = 50.0;
+            let mut food = FoodParticle::drop(FoodType::Pellets, rng);
+            food.position = fish.position;
+            tank.food.push(food);
+        }
+        sim
+    }
+
+    /// Everything the simulation moves forward, in a comparable form
+    fn state(sim: &Simulation) -> serde_json::Value {
+        serde_json::json!({
+            "tanks": sim.save_data.tanks,
+            "total_time": sim.save_data.total_time,
+            "rng": sim.save_data.rng,
+        })
+    }
+
+    #[test]
+    fn same_seed_gives_same_state() {
+        let mut first = busy(7);
+        let mut second = busy(7);
+        let mut events = Vec::new();
+        for _ in 0..600 {
+            first.advance(FIXED_STEP);
+            second.advance(FIXED_STEP);
+            let drained = first.drain_events();
+            assert_eq!(drained, second.drain_events());
+            events.extend(drained);
+        }
+        assert_eq!(state(&first), state(&second));
+
+        // The run has to have exercised the rules that roll dice
+        assert!(events.iter().any(|e| matches!(e, SimEvent::FishBorn { .. })));
+        assert!(first.tank().fish.iter().any(|fish| !fish.diet.is_empty()));
+    }
+
+    #[test]
+    fn stranded_fish_keep_their_family_record() {
+        let mut sim = stocked(5);
+        let fish = Box::new(sim.save_data.tank_mut().fish.remove(0));
+        let id = fish.id;
+        sim.save_data.tank_mut().incidents.push(Incident::JumpedOut { fish, hours_left: 0.5 });
+        assert_eq!(sim.family_member(id).map(|record| record.id), Some(id));
+    }
+
+    #[test]
+    fn restart_keeps_removed_fish_in_the_lineage() {
+        let mut sim = stocked(9);
+        let ids: Vec<Uuid> = sim.tank().fish.iter().map(|fish| fish.id).collect();
+        sim.restart();
+        assert!(sim.tank().fish.is_empty());
+        for id in ids {
+            assert_eq!(sim.family_member(id).map(|record| record.id), Some(id));
+        }
+    }
+
+    #[test]
+    fn large_and_small_deltas_give_same_state() {
+        let mut large = stocked(11);
+        let mut small = stocked(11);
+        let steps = MAX_STEPS_PER_ADVANCE;
+        for _ in 0..20 {
+            assert_eq!(large.advance(FIXED_STEP * steps as f64), steps);
+            for _ in 0..steps {
+                assert_eq!(small.advance(FIXED_STEP), 1);
+            }
+        }
+        assert_eq!(state(&large), state(&small));
+    }
+
+    #[test]
+    fn long_gaps_are_replayed_not_dropped() {
+        let mut sim = stocked(3);
+        let hour = 3600.0;
+        let report = sim.run_for(hour).expect("an hour with fish in the tank is reported");
+        assert_eq!(report.elapsed_seconds, hour);
+        assert!((sim.save_data.total_time - hour * TIME_SCALE).abs() < 1e-6);
+        assert!(sim.tank().fish.iter().all(|fish| fish.age.num_seconds() >= (hour * OFFLINE_TIME_SCALE) as i64 - 1));
+
+        // Ordinary frames still step normally
+        assert!(sim.run_for(FIXED_STEP / TIME_SCALE).is_none());
+    }
+
+    #[test]
+    fn away_report_tells_clock_from_simulated_time() {
+        let mut sim = stocked(4);
+        let away = 2.0 * MAX_OFFLINE_SECONDS;
+        let report = sim.apply_offline_progress(away).unwrap();
+        assert!(report.capped());
+        assert_eq!(report.clock_seconds, away * TIME_SCALE);
+        assert_eq!(report.simulated_seconds, MAX_OFFLINE_SECONDS * OFFLINE_TIME_SCALE);
+        for fish in sim.tank().fish.iter().filter(|fish| fish.alive) {
+            assert_eq!(fish.age.num_seconds() as f64, report.simulated_seconds);
+        }
+    }
+
+    #[test]
+    fn catch_up_is_capped() {
+        let mut sim = stocked(3);
+        assert_eq!(sim.advance(3600.0), MAX_STEPS_PER_ADVANCE);
+
+        // Only `MAX_BACKLOG` of the hour is ever caught up
+        let mut steps = MAX_STEPS_PER_ADVANCE;
+        loop {
+            let taken = sim.advance(0.0);
+            assert!(taken <= MAX_STEPS_PER_ADVANCE);
+            if taken == 0 {
+                break;
+            }
+            steps += taken;
+        }
+        assert_eq!(steps, (MAX_BACKLOG / FIXED_STEP).round() as usize);
+    }
+}
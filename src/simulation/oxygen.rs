@@ -0,0 +1,63 @@
+//! Dissolved oxygen.
+//!
+//! Warm water holds less oxygen than cool water. Fish breathe it and
+//! rotting waste uses it up, and it comes back in through the surface,
+//! faster the more filters and air stones keep the surface moving. Plants
+//! add oxygen under light and use a little in the dark.
+
+use crate::persistence::WaterParams;
+
+/// Share of the gap to saturation made up per game hour through a still
+/// surface
+const STILL_EXCHANGE: f32 = 0.3;
+
+/// Extra exchange per game hour per unit of aeration (one clean air stone)
+const AGITATED_EXCHANGE: f32 = 1.0;
+
+/// Oxygen (mg/L) used per game hour per centimetre of fish in 100 L
+const RESPIRATION: f32 = 0.015;
+
+/// Oxygen (mg/L) used per game hour per unit of rotting waste
+const DECAY_DEMAND: f32 = 0.01;
+
+/// Oxygen (mg/L) plants give off per game hour under full light, and use
+/// per game hour in the dark
+const PHOTOSYNTHESIS: f32 = 0.4;
+const PLANT_RESPIRATION: f32 = 0.1;
+
+/// How far (mg/L) plants can push the water past saturation
+const SUPERSATURATION: f32 = 2.0;
+
+/// Oxygen (mg/L) fully aerated water holds at `temperature` °C
+pub fn saturation(temperature: f32) -> f32 {
+    let t = temperature.clamp(0.0, 40.0);
+    14.6 - 0.41 * t + 0.008 * t * t - 0.00008 * t * t * t
+}
+
+/// Advance dissolved oxygen by `hours` game hours. `bioload` is in
+/// centimetres of fish, `aeration` is the running equipment's and `light`
+/// (0.0 - 1.0) only matters with `plants` in the tank.
+pub fn update_oxygen(
+    water: &mut WaterParams,
+    hours: f32,
+    bioload: f32,
+    swing: f32,
+    aeration: f32,
+    plants: bool,
+    light: f32,
+) {
+    let saturation = saturation(water.temperature);
+
+    // Gas exchange at the surface pulls toward saturation
+    let exchange = ((STILL_EXCHANGE + AGITATED_EXCHANGE * aeration) * hours).min(1.0);
+    water.oxygen += (saturation - water.oxygen) * exchange;
+
+    // Fish and rot use it up, faster in less water
+    water.oxygen -= (RESPIRATION * bioload * swing + DECAY_DEMAND * water.waste) * hours;
+
+    if plants {
+        water.oxygen += (PHOTOSYNTHESIS * light - PLANT_RESPIRATION * (1.0 - light)) * hours;
+    }
+
+    water.oxygen = water.oxygen.clamp(0.0, saturation + SUPERSATURATION);
+}
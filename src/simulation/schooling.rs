@@ -0,0 +1,91 @@
+//! Schooling (boids).
+//!
+//! Fish of a schooling species steer toward their neighbours (cohesion),
+//! match their heading (alignment) and keep a little personal space
+//! (separation). Positions use the tank's 0.0-1.0 coordinates, so distances
+//! here are fractions of the tank.
+
+use crate::models::{Fish, FishState, Species};
+
+/// How far a fish can see its schoolmates
+const NEIGHBOUR_RADIUS: f32 = 0.3;
+
+/// Closer than this and fish push apart
+const PERSONAL_SPACE: f32 = 0.08;
+
+const COHESION: f32 = 0.02;
+const ALIGNMENT: f32 = 0.6;
+const SEPARATION: f32 = 0.0004;
+
+/// Speed limits for schooling fish, in tank widths per real second
+const MIN_SPEED: f32 = 0.006;
+const MAX_SPEED: f32 = 0.02;
+
+/// Where a fish was and where it was heading at the start of the step
+struct Boid {
+    index: usize,
+    species: Species,
+    position: (f32, f32),
+    velocity: (f32, f32),
+}
+
+/// Adjust the velocity of every schooling fish for `dt` real seconds
+pub fn steer(fish: &mut [Fish], dt: f32) {
+    // Steering is worked out from where everyone was at the start of the step
+    let snapshot: Vec<Boid> = fish
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| f.alive)
+        .map(|(index, f)| Boid { index, species: f.species.clone(), position: f.position, velocity: f.velocity })
+        .collect();
+
+    for (i, fish_i) in fish.iter_mut().enumerate() {
+        if !fish_i.alive || fish_i.state == FishState::Resting || fish_i.species.profile().min_school == 0 {
+            continue;
+        }
+
+        let (x, y) = fish_i.position;
+        let mut count = 0.0;
+        let mut centre = (0.0, 0.0);
+        let mut heading = (0.0, 0.0);
+        let mut push = (0.0, 0.0);
+
+        for other in &snapshot {
+            if other.index == i || other.species != fish_i.species {
+                continue; // Only their own kind
+            }
+            let ((ox, oy), (ovx, ovy)) = (other.position, other.velocity);
+            let (dx, dy) = (ox - x, oy - y);
+            let dist = (dx * dx + dy * dy).sqrt();
+            if dist > NEIGHBOUR_RADIUS {
+                continue;
+            }
+            count += 1.0;
+            centre = (centre.0 + ox, centre.1 + oy);
+            heading = (heading.0 + ovx, heading.1 + ovy);
+            if dist < PERSONAL_SPACE && dist > 0.0 {
+                let away = 1.0 / (dist * dist);
+                push = (push.0 - dx * away, push.1 - dy * away);
+            }
+        }
+
+        if count == 0.0 {
+            continue; // Nobody to school with
+        }
+
+        let centre = (centre.0 / count, centre.1 / count);
+        let heading = (heading.0 / count, heading.1 / count);
+        let (vx, vy) = fish_i.velocity;
+        let ax = (centre.0 - x) * COHESION + (heading.0 - vx) * ALIGNMENT + push.0 * SEPARATION;
+        let ay = (centre.1 - y) * COHESION + (heading.1 - vy) * ALIGNMENT + push.1 * SEPARATION;
+
+        let (mut vx, mut vy) = (vx + ax * dt, vy + ay * dt);
+        let speed = (vx * vx + vy * vy).sqrt();
+        if speed > MAX_SPEED || (speed < MIN_SPEED && speed > 0.0) {
+            let scale = speed.clamp(MIN_SPEED, MAX_SPEED) / speed;
+            vx *= scale;
+            vy *= scale;
+        }
+        fish_i.velocity = (vx, vy);
+    }
+}
@@ -0,0 +1,90 @@
+//! Stocking levels.
+//!
+//! How many fish a tank can hold depends on its volume and filtration, and
+//! on how big each fish grows and how much waste it makes. A tank can be
+//! stocked past capacity, but the extra waste outpaces the filter and fouls
+//! the water.
+
+use crate::models::{Equipment, Fish};
+use crate::persistence::Tank;
+
+/// Centimetres of adult fish each litre of unfiltered water supports
+const CM_PER_LITRE: f32 = 0.5;
+
+/// Extra purity lost per game hour for every 100% of overstocking
+const OVERSTOCK_DEGRADATION: f32 = 2.0;
+
+/// How many centimetres of adult fish a tank of `litres` can keep healthy
+pub fn capacity(litres: f32, equipment: &Equipment) -> f32 {
+    litres * CM_PER_LITRE * equipment.capacity_multiplier()
+}
+
+/// Total bioload of the living fish, in centimetres of adult fish
+pub fn bioload(fish: &[Fish]) -> f32 {
+    fish.iter().map(|f| f.bioload()).fold(0.0, |total, load| total + load) // An empty sum would be -0.0
+}
+
+/// Bioload of `tank` as a share of its capacity; above 1.0 it's overstocked
+pub fn level(tank: &Tank) -> f32 {
+    bioload(&tank.fish) / capacity(tank.size.litres(), &tank.equipment)
+}
+
+/// How far past capacity the tank is; 0.0 when within it, 1.0 when double
+pub fn overstock(stocking: f32) -> f32 {
+    (stocking - 1.0).max(0.0)
+}
+
+/// Purity lost per game hour on top of the usual, at `stocking`
+pub fn extra_degradation(stocking: f32) -> f32 {
+    overstock(stocking) * OVERSTOCK_DEGRADATION
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{EquipmentItem, EquipmentModel, Genome, Species, TankSize};
+    use crate::simulation::SimRng;
+
+    /// Average-sized fish of `species`
+    fn fish(species: &str, rng: &mut SimRng) -> Fish {
+        let mut fish = Fish::new(Species::new(species), species.to_string(), rng);
+        fish.genome = Genome::default();
+        fish
+    }
+
+    #[test]
+    fn capacity_grows_with_volume_and_filtration() {
+        let bare = Equipment::default();
+        let filtered = Equipment { items: vec![EquipmentItem::new(EquipmentModel::CanisterFilter)] };
+        assert_eq!(capacity(100.0, &bare), 100.0 * CM_PER_LITRE);
+        assert!(capacity(200.0, &bare) > capacity(100.0, &bare));
+        assert!(capacity(100.0, &filtered) > capacity(100.0, &bare));
+    }
+
+    #[test]
+    fn bigger_fish_load_the_tank_more_and_dead_ones_not_at_all() {
+        let mut rng = SimRng::new(1);
+        let neon = fish("NeonTetra", &mut rng);
+        let goldfish = fish("Goldfish", &mut rng);
+        assert!(goldfish.bioload() > neon.bioload());
+        assert_eq!(bioload(&[]), 0.0);
+
+        let mut dead = goldfish.clone();
+        dead.alive = false;
+        assert_eq!(bioload(&[goldfish.clone(), dead]), goldfish.bioload());
+    }
+
+    #[test]
+    fn overstocking_fouls_the_water() {
+        let mut rng = SimRng::new(2);
+        let mut tank = Tank::new("Test");
+        tank.size = TankSize::Nano;
+        assert_eq!(level(&tank), 0.0);
+        while level(&tank) <= 1.0 {
+            tank.fish.push(fish("Goldfish", &mut rng));
+        }
+        assert!(extra_degradation(level(&tank)) > 0.0);
+        assert_eq!(extra_degradation(0.9), 0.0);
+        assert_eq!(overstock(2.0), 1.0);
+    }
+}
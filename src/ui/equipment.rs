@@ -0,0 +1,77 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use fishtank::models::EquipmentItem;
+
+use crate::app::{App, EquipmentRow};
+use crate::utils::draw_stat_bar;
+
+use super::centered_rect;
+
+/// Equipment in the tank, spares in the cupboard and new equipment, with
+/// row `selected` highlighted
+pub fn render_equipment_menu(frame: &mut Frame, app: &App, selected: usize, area: Rect) {
+    let area = centered_rect(60, 70, area);
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(
+            format!("⚙️ Equipment - {}", app.sim.tank().name),
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+        .style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let heading = |text: &'static str| Line::from(Span::styled(text, Style::default().fg(Color::Yellow)));
+    let mut lines = Vec::new();
+    let mut section = None;
+    let mut selected_line = 0;
+    for (row_index, row) in app.equipment_rows().into_iter().enumerate() {
+        // Heading whenever a new section starts
+        let (title, text) = match row {
+            EquipmentRow::Installed(index) => ("In the tank", describe(&app.sim.tank().equipment.items[index])),
+            EquipmentRow::Spare(index) => ("In the cupboard", describe(&app.sim.save_data.storage[index])),
+            EquipmentRow::New(model) => ("New", format!("{} {}", model.kind().icon(), model.name())),
+        };
+        if section != Some(title) {
+            if section.is_some() {
+                lines.push(Line::from(""));
+            }
+            lines.push(heading(title));
+            section = Some(title);
+        }
+
+        let style = if row_index == selected {
+            selected_line = lines.len();
+            Style::default().fg(Color::White).add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        lines.push(Line::from(Span::styled(format!("  {}", text), style)));
+    }
+
+    // Keep the selection on screen in a short terminal
+    let scroll = (selected_line as u16 + 1).saturating_sub(inner.height);
+    frame.render_widget(Paragraph::new(lines).scroll((scroll, 0)), inner);
+}
+
+/// "⚡ Hang-on filter  ███░░░░░░░ 30% worn  at 85%"
+fn describe(item: &EquipmentItem) -> String {
+    format!(
+        "{} {:<16}{} {:>3.0}% worn  at {:.0}%{}",
+        item.model.kind().icon(),
+        item.model.name(),
+        draw_stat_bar(item.wear, 10),
+        item.wear,
+        item.position * 100.0,
+        if item.needs_service() { "  needs cleaning" } else { "" },
+    )
+}
@@ -0,0 +1,126 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use uuid::Uuid;
+
+use fishtank::models::{Gender, LineageRecord};
+use fishtank::Simulation;
+
+use super::centered_rect;
+
+/// Generations drawn above and below the selected fish
+const MAX_DEPTH: usize = 3;
+
+/// Family tree of one fish: its ancestors above, its descendants below
+pub fn render_family_tree(frame: &mut Frame, sim: &Simulation, id: Uuid, area: Rect) {
+    let area = centered_rect(80, 80, area);
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled("🌳 Family Tree", Style::default().add_modifier(Modifier::BOLD)))
+        .style(Style::default().fg(Color::Green));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let now = sim.save_data.total_time;
+    let mut lines = Vec::new();
+    match sim.family_member(id) {
+        Some(fish) => {
+            lines.push(Line::from(Span::styled(
+                format!("{} ({})", describe(&fish, now), fish.species.name()),
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+            )));
+
+            let heading = |text: &'static str| Line::from(Span::styled(text, Style::default().fg(Color::Cyan)));
+            lines.push(Line::from(""));
+            lines.push(heading("Ancestors"));
+            let mut ancestors = Vec::new();
+            ancestor_lines(sim, &fish, "", 1, &mut ancestors);
+            if ancestors.is_empty() {
+                ancestors.push("  Shop-bought, no known parents".to_string());
+            }
+            lines.extend(ancestors.into_iter().map(Line::from));
+
+            lines.push(Line::from(""));
+            lines.push(heading("Descendants"));
+            let mut descendants = Vec::new();
+            descendant_lines(sim, fish.id, "", 1, &mut descendants);
+            if descendants.is_empty() {
+                descendants.push("  None yet".to_string());
+            }
+            lines.extend(descendants.into_iter().map(Line::from));
+        }
+        None => lines.push(Line::from("No record of this fish.")),
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "[←/→] Other fish   [L/Enter] Close",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// One-line summary: name, gender, generation, birth day and fate, with the
+/// game clock at `now`
+fn describe(fish: &LineageRecord, now: f64) -> String {
+    let gender = match fish.gender {
+        Gender::Male => "♂",
+        Gender::Female => "♀",
+    };
+    let mut text = format!("{} {} gen {}, born day {}", fish.name, gender, fish.generation, day(fish.birth_clock(now)));
+    if !fish.alive {
+        let cause = fish.cause_of_death.map_or("unknown causes", |c| c.description());
+        match fish.died_at {
+            Some(died_at) => text.push_str(&format!(" † day {} ({})", day(died_at), cause)),
+            None => text.push_str(&format!(" † ({})", cause)),
+        }
+    }
+    text
+}
+
+/// Game day (starting at 1) for a game clock value
+fn day(game_seconds: f64) -> u64 {
+    (game_seconds.max(0.0) / 86_400.0) as u64 + 1
+}
+
+/// Branch glyphs for the last or a middle child, and the prefix its own
+/// children continue with
+fn branch(last: bool) -> (&'static str, &'static str) {
+    if last { ("└─ ", "   ") } else { ("├─ ", "│  ") }
+}
+
+fn ancestor_lines(sim: &Simulation, fish: &LineageRecord, prefix: &str, depth: usize, out: &mut Vec<String>) {
+    let Some((mother, father)) = fish.parents else { return };
+    for (label, id, last) in [("Mother", mother, false), ("Father", father, true)] {
+        let (glyph, indent) = branch(last);
+        match sim.family_member(id) {
+            Some(parent) => {
+                out.push(format!("  {}{}{}: {}", prefix, glyph, label, describe(&parent, sim.save_data.total_time)));
+                if depth < MAX_DEPTH {
+                    ancestor_lines(sim, &parent, &format!("{}{}", prefix, indent), depth + 1, out);
+                }
+            }
+            None => out.push(format!("  {}{}{}: unknown", prefix, glyph, label)),
+        }
+    }
+}
+
+fn descendant_lines(sim: &Simulation, id: Uuid, prefix: &str, depth: usize, out: &mut Vec<String>) {
+    let children = sim.children_of(id);
+    let count = children.len();
+    for (i, child) in children.iter().enumerate() {
+        let (glyph, indent) = branch(i + 1 == count);
+        out.push(format!("  {}{}{}", prefix, glyph, describe(child, sim.save_data.total_time)));
+        if depth < MAX_DEPTH {
+            descendant_lines(sim, child.id, &format!("{}{}", prefix, indent), depth + 1, out);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use fishtank::models::LightSetting;
+
+use crate::app::App;
+use crate::utils::draw_stat_bar;
+
+use super::centered_rect;
+
+/// Timer and intensity of the light over the tank on screen, with setting
+/// `selected` highlighted
+pub fn render_light_menu(frame: &mut Frame, app: &App, selected: usize, area: Rect) {
+    let area = centered_rect(40, 40, area);
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(
+            format!("💡 Light - {}", app.sim.tank().name),
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+        .style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let light = app.sim.tank().light;
+    let mut lines = Vec::new();
+    for (index, setting) in LightSetting::ALL.into_iter().enumerate() {
+        let value = match setting {
+            LightSetting::OnAt => format!("{:02}:00", light.on_hour),
+            LightSetting::OffAt => format!("{:02}:00", light.off_hour),
+            LightSetting::Intensity => format!("{} {:.0}%", draw_stat_bar(light.intensity * 100.0, 10), light.intensity * 100.0),
+        };
+        let style = if index == selected {
+            Style::default().fg(Color::White).add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        lines.push(Line::from(Span::styled(format!("  {:<10}{}", setting.name(), value), style)));
+    }
+
+    let (hour, _) = app.get_game_time();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!(
+            "  {} h of light a day, {} now",
+            light.photoperiod(),
+            if light.is_on(hour) { "on" } else { "off" }
+        ),
+        Style::default().fg(Color::Yellow),
+    )));
+    lines.push(Line::from(Span::styled(
+        "  Plants and algae grow under light; fish sleep without it.",
+        Style::default().fg(Color::Gray),
+    )));
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
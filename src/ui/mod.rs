@@ -2,14 +2,19 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
+use std::cell::Cell;
 
 use crate::app::App;
-use crate::models::FishState;
-use crate::utils::{draw_stat_bar, stat_color_indicator, FishSprite, TankElements};
+use fishtank::models::{light::DARK, EquipmentKind, Incident, TankSize};
+use fishtank::simulation::{OfflineReport, ParamRange, SimEvent};
+use crate::utils::{draw_stat_bar, stat_color_indicator, FishSprite};
 
+pub mod equipment;
+pub mod lighting;
+pub mod family_tree;
 pub mod theme;
 
 pub fn render(frame: &mut Frame, app: &App) {
@@ -35,22 +40,275 @@ pub fn render(frame: &mut Frame, app: &App) {
     render_tank(frame, app, main_chunks[0]);
     render_stats(frame, app, main_chunks[1]);
     render_controls(frame, app, chunks[1]);
+
+    if let Some(selected) = app.equipment_menu {
+        equipment::render_equipment_menu(frame, app, selected, size);
+    }
+    if let Some(selected) = app.light_menu {
+        lighting::render_light_menu(frame, app, selected, size);
+    }
+    if let Some(id) = app.family_tree {
+        family_tree::render_family_tree(frame, &app.sim, id, size);
+    }
+    if let Some(report) = &app.away_report {
+        render_away_report(frame, report, &app.away_scroll, size);
+    }
+}
+
+/// Rect of the given percentage size centered in `area`
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+/// "While you were away" modal shown on startup
+fn render_away_report(frame: &mut Frame, report: &OfflineReport, scroll: &Cell<u16>, area: Rect) {
+    let area = centered_rect(70, 80, area);
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled("⏰ While You Were Away", Style::default().add_modifier(Modifier::BOLD)))
+        .style(Style::default().fg(Color::Yellow));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let heading = |text: &'static str| Line::from(Span::styled(text, Style::default().fg(Color::Cyan)));
+    let mut lines = Vec::new();
+
+    lines.push(Line::from(format!(
+        "Away for {} ({} on the game clock)",
+        App::format_duration(report.elapsed_seconds),
+        App::format_duration(report.clock_seconds),
+    )));
+    lines.push(Line::from(Span::styled(
+        format!(
+            "  Fish and water lived through {}{}",
+            App::format_duration(report.simulated_seconds),
+            if report.capped() { "; the tanks slept through the rest" } else { "" },
+        ),
+        Style::default().fg(Color::Gray),
+    )));
+
+    // Births
+    lines.push(Line::from(""));
+    let births: Vec<&SimEvent> = report.births().collect();
+    lines.push(heading("🐣 Births"));
+    if births.is_empty() {
+        lines.push(Line::from("  None"));
+    }
+    for event in births {
+        if let SimEvent::FishBorn { name, species } = event {
+            lines.push(Line::from(format!("  {} ({})", name, species.name())));
+        }
+    }
+
+    // Deaths
+    lines.push(Line::from(""));
+    let deaths: Vec<&SimEvent> = report.deaths().collect();
+    lines.push(heading("💀 Deaths"));
+    if deaths.is_empty() {
+        lines.push(Line::from("  None"));
+    }
+    for event in deaths {
+        if let SimEvent::FishDied { name, species, cause } = event {
+            let cause = cause.map_or("unknown causes", |c| c.description());
+            lines.push(Line::from(Span::styled(
+                format!("  {} ({}) - {}", name, species.name(), cause),
+                Style::default().fg(Color::Red),
+            )));
+        }
+    }
+
+    // Illness, only when there was any
+    let illnesses: Vec<&SimEvent> = report.illnesses().collect();
+    if !illnesses.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(heading("🦠 Illness"));
+    }
+    for event in illnesses {
+        match event {
+            SimEvent::FishFellIll { name, disease, .. } => lines.push(Line::from(Span::styled(
+                format!("  {} caught {}", name, disease.name()),
+                Style::default().fg(Color::Yellow),
+            ))),
+            SimEvent::FishRecovered { name, disease, .. } => {
+                lines.push(Line::from(format!("  {} recovered from {}", name, disease.name())))
+            }
+            _ => {}
+        }
+    }
+
+    // Incidents
+    let incidents: Vec<&SimEvent> = report.incidents().collect();
+    if !incidents.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(heading("🚨 Incidents"));
+    }
+    for event in incidents {
+        match event {
+            SimEvent::IncidentStarted { tank, kind } => lines.push(Line::from(Span::styled(
+                format!("  {}: {}", tank, kind.alert()),
+                Style::default().fg(Color::Red),
+            ))),
+            SimEvent::IncidentOver { tank, kind } => lines.push(Line::from(format!("  {}: {}", tank, kind.over()))),
+            _ => {}
+        }
+    }
+
+    // Each tank's water, equipment and fish
+    let range = |label: &str, r: &ParamRange, unit: &str| {
+        Line::from(format!("  {:<7}{:.1}{} - {:.1}{}", label, r.min, unit, r.max, unit))
+    };
+    for tank in &report.tanks {
+        if report.tanks.len() > 1 {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!("🏠 {}", tank.name),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )));
+        }
+
+        // Water
+        lines.push(Line::from(""));
+        lines.push(heading("💧 Water (min - max)"));
+        lines.push(range("Purity", &tank.water.purity, "%"));
+        lines.push(range("Temp", &tank.water.temperature, "°C"));
+        lines.push(range("pH", &tank.water.ph, ""));
+        lines.push(range("NH3", &tank.water.ammonia, " ppm"));
+        lines.push(range("NO2", &tank.water.nitrite, " ppm"));
+        lines.push(range("NO3", &tank.water.nitrate, " ppm"));
+        lines.push(range("O2", &tank.water.oxygen, " mg/L"));
+
+        // Equipment
+        lines.push(Line::from(""));
+        lines.push(heading("⚙️ Equipment"));
+        if tank.equipment.items.is_empty() {
+            lines.push(Line::from("  Nothing installed"));
+        }
+        for item in &tank.equipment.items {
+            let style = if item.needs_service() { Style::default().fg(Color::Yellow) } else { Style::default() };
+            lines.push(Line::from(Span::styled(
+                format!("  {} {} ({:.0}% worn)", item.model.kind().icon(), item.model.name(), item.wear),
+                style,
+            )));
+        }
+
+        // Per-fish changes
+        if tank.fish.is_empty() {
+            continue;
+        }
+        lines.push(Line::from(""));
+        lines.push(heading("🐟 Fish"));
+        for change in &tank.fish {
+            let mut spans = vec![Span::styled(
+                format!("  {:<10}", change.name),
+                Style::default().add_modifier(Modifier::BOLD),
+            )];
+            if change.alive {
+                spans.push(stat_delta_span("❤", change.health));
+                spans.push(stat_delta_span("🍗", change.hunger));
+                spans.push(stat_delta_span("😊", change.happiness));
+                spans.push(stat_delta_span("⚡", change.energy));
+            } else {
+                spans.push(Span::styled("💀 Deceased", Style::default().fg(Color::DarkGray)));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "[↑/↓] Scroll   [Enter] Continue",
+        Style::default().fg(Color::Gray),
+    )));
+
+    // Stop scrolling once the last line is in view
+    scroll.set(scroll.get().min((lines.len() as u16).saturating_sub(inner.height)));
+    frame.render_widget(Paragraph::new(lines).scroll((scroll.get(), 0)), inner);
+}
+
+/// "❤ 80→62 " colored by direction of change
+fn stat_delta_span(label: &str, (before, after): (f32, f32)) -> Span<'static> {
+    let color = if after > before + 0.5 {
+        Color::Green
+    } else if after < before - 5.0 {
+        Color::Red
+    } else {
+        Color::White
+    };
+    Span::styled(format!("{}{:.0}→{:.0} ", label, before, after), Style::default().fg(color))
+}
+
+/// Smallest glass drawn, in cells, so sprites and messages still fit
+const MIN_GLASS_WIDTH: u16 = 24;
+const MIN_GLASS_HEIGHT: u16 = 8;
+
+/// Draw the tank's glass to scale against the largest size, standing on the
+/// bottom of `area`, and return the space inside it for the water
+fn glass_area(frame: &mut Frame, area: Rect, size: TankSize, color: Color) -> Rect {
+    let (width, height) = size.front_pane();
+    if width >= 1.0 && height >= 1.0 {
+        return area; // Fills the whole panel
+    }
+
+    let width = ((area.width as f32 * width).round() as u16).clamp(MIN_GLASS_WIDTH.min(area.width), area.width);
+    let height = ((area.height as f32 * height).round() as u16).clamp(MIN_GLASS_HEIGHT.min(area.height), area.height);
+    let glass = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + area.height - height,
+        width,
+        height,
+    };
+
+    // Open at the top, like a real tank
+    let block = Block::default()
+        .borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM)
+        .style(Style::default().fg(color));
+    let inner = block.inner(glass);
+    frame.render_widget(block, glass);
+    inner
 }
 
 fn render_tank(frame: &mut Frame, app: &App, area: Rect) {
     let (hour, minute) = app.get_game_time();
     let is_night = app.is_night();
+    // The tank's light, not the sun, decides how dark it looks
+    let is_dark = app.sim.light_level() < DARK;
     
     // Time indicator with emoji
     let time_emoji = if is_night { "🌙" } else { "🌞" };
-    let time_str = format!("{} {:02}:{:02}", time_emoji, hour, minute);
+    let light_emoji = if app.sim.tank().light.is_on(hour) { " 💡" } else { "" };
+    let time_str = format!("{} {:02}:{:02}{}", time_emoji, hour, minute, light_emoji);
     
     let theme = app.get_current_theme();
     
-    let title = if app.save_data.is_frozen {
-        format!("Fish Tank - {} ❄️ FROZEN ❄️", theme.name)
+    let tanks = app.sim.save_data.tanks.len();
+    let tank_name = if tanks > 1 {
+        format!("{} [{}/{}]", app.sim.tank().name, app.sim.save_data.active_tank + 1, tanks)
     } else {
-        format!("Fish Tank - {}", theme.name)
+        app.sim.tank().name.clone()
+    };
+    let title = if app.sim.save_data.is_frozen {
+        format!("{} - {} ❄️ FROZEN ❄️", tank_name, theme.name)
+    } else {
+        format!("{} - {}", tank_name, theme.name)
     };
 
     let block = Block::default()
@@ -65,6 +323,7 @@ fn render_tank(frame: &mut Frame, app: &App, area: Rect) {
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
+    let inner = glass_area(frame, inner, app.sim.tank().size, theme.border_color);
 
     // Tank rendering area
     let tank_width = inner.width as usize;
@@ -76,8 +335,8 @@ fn render_tank(frame: &mut Frame, app: &App, area: Rect) {
     // Theme Colors
     let theme = app.get_current_theme();
     
-    // Adjust for night time (dimming)
-    let (substrate_color, plant_color, bubble_color) = if is_night {
+    // Adjust for the lights being out (dimming)
+    let (substrate_color, plant_color, bubble_color) = if is_dark {
          // Simple dimming logic: if theme is classic, use hardcoded night colors
          // Otherwise, maybe just use theme colors but dimmed? 
          // For now, let's respect the theme colors but maybe swap to darker variants if it's default
@@ -90,22 +349,20 @@ fn render_tank(frame: &mut Frame, app: &App, area: Rect) {
         (theme.substrate_color, theme.plant_color, theme.water_color)
     };
     
-    for y in 0..tank_height {
-        for x in 0..tank_width {
+    for (y, row) in buffer.iter_mut().enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
              if y == tank_height - 1 {
-                buffer[y][x] = Span::styled(theme.substrate_char.to_string(), Style::default().fg(substrate_color));
+                *cell = Span::styled(theme.substrate_char.to_string(), Style::default().fg(substrate_color));
             } else if y == tank_height - 2 && (x < 3 || x > tank_width - 4) {
-                 buffer[y][x] = Span::styled("Y", Style::default().fg(plant_color));
-            } else if y == 0 && x % 15 == 0 && app.animation_frame % 60 < 30 && !is_night && !app.save_data.is_frozen {
-                 buffer[y][x] = Span::styled("°", Style::default().fg(bubble_color));
-            } else if app.save_data.is_frozen && y == tank_height / 2 && x == tank_width / 2 {
-                 // Nothing specifically, maybe freeze overlay logic later
+                 *cell = Span::styled("Y", Style::default().fg(plant_color));
+            } else if y == 0 && x % 15 == 0 && app.animation_frame % 60 < 30 && !is_dark && !app.sim.save_data.is_frozen {
+                 *cell = Span::styled("°", Style::default().fg(bubble_color));
             }
         }
     }
 
     // Render Decorations (Background Layer)
-    for deco in &app.save_data.decorations {
+    for deco in &app.sim.tank().decorations {
         let sprite_lines = deco.get_sprite();
         let sprite_height = sprite_lines.len();
         
@@ -125,18 +382,18 @@ fn render_tank(frame: &mut Frame, app: &App, area: Rect) {
             for char in line.chars() {
                 if current_x < tank_width {
                     // Use a subtle color for decorations
-                     let color = if is_night {
-                         // Slightly visible at night
+                     let color = if is_dark {
+                         // Slightly visible in the dark
                          match deco.deco_type {
-                             crate::models::DecorationType::Plant => Color::Green, // Keep green but maybe it will look dark on black
+                             fishtank::models::DecorationType::Plant => Color::Green, // Keep green but maybe it will look dark on black
                              _ => Color::Gray,
                          }
                      } else {
                          match deco.deco_type {
-                             crate::models::DecorationType::Plant => theme.plant_color,
-                             crate::models::DecorationType::Rock => Color::Gray,
-                             crate::models::DecorationType::Castle => Color::White,
-                             crate::models::DecorationType::Skull => Color::White,
+                             fishtank::models::DecorationType::Plant => theme.plant_color,
+                             fishtank::models::DecorationType::Rock => Color::Gray,
+                             fishtank::models::DecorationType::Castle => Color::White,
+                             fishtank::models::DecorationType::Skull => Color::White,
                          }
                      };
                     
@@ -147,16 +404,56 @@ fn render_tank(frame: &mut Frame, app: &App, area: Rect) {
         }
     }
 
+    // Render Equipment (in front of decorations, behind the fish). A lid
+    // goes across the whole top, under anything hanging from the rim.
+    if app.sim.tank().equipment.has(EquipmentKind::Lid) {
+        for cell in buffer[0].iter_mut() {
+            *cell = Span::styled("▁", Style::default().fg(Color::Gray));
+        }
+    }
+    for item in &app.sim.tank().equipment.items {
+        let kind = item.model.kind();
+        let sprite = kind.sprite();
+        let sprite_width = sprite.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        let base_x = (item.position * tank_width.saturating_sub(sprite_width) as f32).round() as usize;
+        let base_y = match kind {
+            EquipmentKind::Filter => 0,
+            EquipmentKind::Heater => 1,
+            EquipmentKind::Plants | EquipmentKind::AirPump => (tank_height - 1).saturating_sub(sprite.len()),
+            EquipmentKind::Lid => 0,
+        };
+        let color = match kind {
+            EquipmentKind::Filter if item.needs_service() => Color::Rgb(120, 90, 50), // Brown with muck
+            EquipmentKind::Filter => Color::Gray,
+            EquipmentKind::Heater => Color::Rgb(200, 80, 40),
+            EquipmentKind::AirPump => Color::Rgb(120, 120, 140), // Grey stone
+            EquipmentKind::Plants | EquipmentKind::Lid => plant_color,
+        };
+
+        for (offset_y, line) in sprite.iter().enumerate() {
+            let y = base_y + offset_y;
+            if y >= tank_height - 1 { continue; } // Never over the substrate
+            for (offset_x, char) in line.chars().enumerate() {
+                let x = base_x + offset_x;
+                if x < tank_width && char != ' ' {
+                    buffer[y][x] = Span::styled(char.to_string(), Style::default().fg(color));
+                }
+            }
+        }
+    }
+
     // Render Fish
-    if !app.save_data.fish.is_empty() {
-        for fish in &app.save_data.fish {
+    if !app.sim.tank().fish.is_empty() {
+        let alpha = app.sim.interpolation_alpha();
+        for fish in &app.sim.tank().fish {
             if !fish.alive {
                 continue;
             }
             
             // Calculate base position (top-left of sprite)
-            let base_y = (fish.position.1 * (tank_height - 2) as f32).round() as usize;
-            let base_x = (fish.position.0 * (tank_width - 5) as f32).round() as usize;
+            let (fish_x, fish_y) = fish.render_position(alpha);
+            let base_y = (fish_y * (tank_height - 2) as f32).round() as usize;
+            let base_x = (fish_x * (tank_width - 5) as f32).round() as usize;
             
             let sprite_lines = FishSprite::from_fish(fish, app.animation_frame);
             
@@ -167,19 +464,22 @@ fn render_tank(frame: &mut Frame, app: &App, area: Rect) {
                 let mut current_x = base_x;
                 for char in line.chars() {
                     if current_x < tank_width {
-                        // Use Species specific color or Theme default?
-                        // Let's use Theme default for special themes like Matrix/Retro
+                        // Species color, except on themes with their own palette
                         let color = if theme.name == "Matrix" || theme.name == "Retro Amber" || theme.name == "Zen Garden" {
                             theme.fish_default_color
                         } else {
-                            if matches!(fish.stage, crate::models::GrowthStage::Fry) {
-                                 Color::White 
-                             } else {
-                                 Color::Yellow // Default per species ideally, but keep simple for now
-                             }
+                            let [r, g, b] = fish.genome.tint(fish.species.def().color(fish.stage));
+                            Color::Rgb(r, g, b)
                         };
+                        let mut style = Style::default().fg(color);
+                        if fish.stage == fishtank::models::GrowthStage::Elder {
+                            style = style.add_modifier(Modifier::DIM); // Faded with age
+                        }
+                        if app.moving_fish.is_some_and(|(id, _)| id == fish.id) {
+                            style = style.add_modifier(Modifier::REVERSED); // Picked out to move
+                        }
                          
-                        buffer[y][current_x] = Span::styled(char.to_string(), Style::default().fg(color));
+                        buffer[y][current_x] = Span::styled(char.to_string(), style);
                         current_x += 1;
                     }
                 }
@@ -187,7 +487,7 @@ fn render_tank(frame: &mut Frame, app: &App, area: Rect) {
         }
     } else {
         // Empty tank message
-        let empty_msg = "Press 'N' to add fish (up to 3)!";
+        let empty_msg = "Press 'N' to add fish!";
         let start_x = (tank_width.saturating_sub(empty_msg.len())) / 2;
         let y = tank_height / 2;
         
@@ -199,7 +499,7 @@ fn render_tank(frame: &mut Frame, app: &App, area: Rect) {
     }
     
     // Draw Frozen Overlay if needed
-    if app.save_data.is_frozen {
+    if app.sim.save_data.is_frozen {
         let msg = "❄️ FROZEN ❄️";
         let start_x = (tank_width.saturating_sub(msg.len())) / 2;
         let y = 1; 
@@ -210,6 +510,20 @@ fn render_tank(frame: &mut Frame, app: &App, area: Rect) {
         }
     }
 
+    // Render Food (sinking flakes, and leftovers rotting on the bottom)
+    let alpha = app.sim.interpolation_alpha();
+    for food in &app.sim.tank().food {
+         let (food_x, food_y) = food.render_position(alpha);
+         let y = (food_y * (tank_height - 1) as f32).round() as usize;
+         let x = (food_x * (tank_width - 1) as f32).round() as usize;
+
+         if y < tank_height - 1 && x < tank_width {
+             let (sinking, settled, [r, g, b]) = food.kind.look();
+             let (symbol, color) = if food.settled() { (settled, Color::Rgb(r / 2, g / 2, b / 2)) } else { (sinking, Color::Rgb(r, g, b)) };
+             buffer[y][x] = Span::styled(symbol.to_string(), Style::default().fg(color));
+         }
+    }
+
     // Render Particles (Foreground)
     for p in &app.particles {
          let y = (p.y * (tank_height - 1) as f32).round() as usize;
@@ -223,23 +537,31 @@ fn render_tank(frame: &mut Frame, app: &App, area: Rect) {
     }
     
     // Render Algae Overlay (Dirty Glass)
-    let algae_level = app.save_data.algae_level;
+    let algae_level = app.sim.tank().algae_level;
     if algae_level > 1.0 {
         let density = algae_level / 100.0; // 0.0 to 1.0
         
-        for y in 0..tank_height-1 { // Don't cover substrate fully? or maybe yes
-            for x in 0..tank_width {
+        for (y, row) in buffer.iter_mut().enumerate().take(tank_height - 1) { // Don't cover substrate
+            for (x, cell) in row.iter_mut().enumerate() {
                 // Simple pseudo-random hash for static noise
                 let seed = (x as u32).wrapping_mul(374761393).wrapping_add((y as u32).wrapping_mul(668265263));
                 let rand_val = (seed % 100) as f32 / 100.0;
                 
                 if rand_val < density {
-                    // Algae pixel!
-                    // If density is high, use thicker chars
-                    let char = if density > 0.6 && rand_val < density * 0.5 { "#" } else { "." };
+                    // Algae pixel! Older growth (lower noise value) is thicker
+                    // and darker, so build-up shows as bands of glyphs
+                    let age = rand_val / density; // 0.0 = oldest, 1.0 = newest
+                    let (char, color) = if density > 0.75 && age < 0.3 {
+                        ("#", Color::Rgb(20, 90, 20))
+                    } else if density > 0.5 && age < 0.5 {
+                        ("%", Color::Rgb(40, 120, 30))
+                    } else if density > 0.25 && age < 0.7 {
+                        (":", Color::Green)
+                    } else {
+                        (".", Color::LightGreen)
+                    };
                     
-                    // Green slime color
-                    buffer[y][x] = Span::styled(char, Style::default().fg(Color::Green));
+                    *cell = Span::styled(char, Style::default().fg(color));
                 }
             }
         }
@@ -266,28 +588,29 @@ fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
 
     let mut lines = Vec::new();
 
-    if app.save_data.fish.is_empty() {
+    if app.sim.tank().fish.is_empty() {
         lines.push(Line::from("No fish in tank"));
         lines.push(Line::from(""));
         lines.push(Line::from("Press 'N' to add"));
-        lines.push(Line::from("up to 10 fish!"));
+        lines.push(Line::from("your first fish!"));
     } else {
         // COMPACT VIEW for many fish
-        if app.save_data.fish.len() > 4 {
-             for fish in &app.save_data.fish {
+        if app.sim.tank().fish.len() > 4 {
+             for fish in &app.sim.tank().fish {
                 let gender_symbol = match fish.gender {
-                    crate::models::Gender::Male => "♂",
-                    crate::models::Gender::Female => "♀",
+                    fishtank::models::Gender::Male => "♂",
+                    fishtank::models::Gender::Female => "♀",
                 };
                 
                 let health_color = if fish.health > 70.0 { Color::Green } else { Color::Red };
                 
                 // Status icon (check sleep/eat)
                 let status_icon = match fish.state {
-                    crate::models::FishState::Resting => " 💤", // Sleeping
-                    crate::models::FishState::Eating => " 🍖",  // Eating
+                    fishtank::models::FishState::Resting => " 💤", // Sleeping
+                    fishtank::models::FishState::Eating => " 🍖",  // Eating
                     _ => "",
                 };
+                let elder_icon = if fish.stage == fishtank::models::GrowthStage::Elder { " 🦴" } else { "" };
                 
                 // One line per fish: [ICON] Name (S) H:99% Zzz
                 if fish.alive {
@@ -298,7 +621,7 @@ fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
                         Span::styled("❤", Style::default().fg(health_color)),
                         Span::raw(format!("{:.0}% ", fish.health)),
                         Span::styled("🍗", Style::default().fg(Color::Magenta)),
-                        Span::raw(format!("{:.0}%{}", fish.hunger, status_icon)),
+                        Span::raw(format!("{:.0}%{}{}", fish.hunger, status_icon, elder_icon)),
                     ]));
                 } else {
                     lines.push(Line::from(vec![
@@ -309,28 +632,58 @@ fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
             }
              
              // Summary at bottom
-             let alive = app.save_data.fish.iter().filter(|f| f.alive).count();
+             let alive = app.sim.tank().fish.iter().filter(|f| f.alive).count();
              lines.push(Line::from(""));
-             lines.push(Line::from(format!("Pop: {}/{}", alive, app.save_data.fish.len())));
+             lines.push(Line::from(format!("Pop: {}/{}", alive, app.sim.tank().fish.len())));
              
         } else {
             // DETAILED VIEW (Original)
-            for (idx, fish) in app.save_data.fish.iter().enumerate() {
+            for (idx, fish) in app.sim.tank().fish.iter().enumerate() {
                 if idx > 0 {
                     lines.push(Line::from(""));
                 }
     
                 let gender_symbol = match fish.gender {
-                    crate::models::Gender::Male => "♂",
-                    crate::models::Gender::Female => "♀",
+                    fishtank::models::Gender::Male => "♂",
+                    fishtank::models::Gender::Female => "♀",
                 };
     
                 lines.push(Line::from(vec![
                     Span::styled("🐟 ", Style::default().fg(Color::Yellow)),
                     Span::styled(&fish.name, Style::default().add_modifier(Modifier::BOLD)),
-                    Span::raw(format!(" ({}) {}", fish.species.name(), gender_symbol)),
+                    Span::raw(format!(" ({}) {} ", fish.species.name(), gender_symbol)),
+                    Span::styled(
+                        format!("{} {}", fish.stage.name(), fish.age_display()),
+                        Style::default().fg(if fish.stage == fishtank::models::GrowthStage::Elder { Color::Gray } else { Color::DarkGray }),
+                    ),
                 ]));
     
+                // Expressed genes
+                let genome = &fish.genome;
+                let temperament = match genome.temperament.value() {
+                    t if t < 0.33 => "placid",
+                    t if t > 0.66 => "aggressive",
+                    _ => "calm",
+                };
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "🧬 {} fins, size {:.2}, speed {:.2}, hardy {:.2}, {}",
+                        genome.fin_shape().name(),
+                        genome.size.value(),
+                        genome.speed.value(),
+                        genome.hardiness.value(),
+                        temperament,
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                )));
+                let favorites: Vec<&str> = fish.species.def().favorite_foods.iter().map(|f| f.name()).collect();
+                if !favorites.is_empty() {
+                    lines.push(Line::from(Span::styled(
+                        format!("🍤 Loves {}", favorites.join(", ")),
+                        Style::default().fg(Color::DarkGray),
+                    )));
+                }
+    
                 if fish.alive {
                     lines.push(Line::from(vec![
                         Span::raw(stat_color_indicator(fish.hunger)),
@@ -340,7 +693,7 @@ fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
                     ]));
     
                     // Show warnings for this fish
-                    let warnings = fish.get_warnings();
+                    let warnings = fish.get_warnings(&app.sim.tank().water, &app.sim.surroundings());
                     if !warnings.is_empty() {
                         for warning in warnings.iter().take(2) {
                             lines.push(Line::from(Span::styled(
@@ -358,17 +711,34 @@ fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
             }
             
             // Summary
-            let alive = app.save_data.fish.iter().filter(|f| f.alive).count();
+            let alive = app.sim.tank().fish.iter().filter(|f| f.alive).count();
             lines.push(Line::from(""));
-            lines.push(Line::from(format!("Alive: {}/{}", alive, app.save_data.fish.len())));
+            lines.push(Line::from(format!("Alive: {}/{}", alive, app.sim.tank().fish.len())));
         }
     }
 
+    // Stocking meter
+    lines.push(Line::from(""));
+    let size = app.sim.tank().size;
+    lines.push(Line::from(vec![
+        Span::styled("📐 Tank: ", Style::default().fg(Color::Cyan)),
+        Span::raw(format!("{} {:.0} L", size.name(), size.litres())),
+    ]));
+    let stocking = app.sim.stocking() * 100.0;
+    let stocking_color = if stocking > 100.0 { Color::Red } else if stocking > 80.0 { Color::Yellow } else { Color::Green };
+    lines.push(Line::from(vec![
+        Span::styled("🐠 Stocking: ", Style::default().fg(Color::Cyan)),
+        Span::styled(format!("{:.0}%", stocking), Style::default().fg(stocking_color)),
+        Span::raw(format!(" of {:.0} cm", app.sim.capacity())),
+        Span::styled(if stocking > 100.0 { " Overstocked!" } else { "" }, Style::default().fg(Color::Red)),
+    ]));
+    lines.push(Line::from(Span::styled(draw_stat_bar(stocking.min(100.0), 10), Style::default().fg(stocking_color))));
+
     // Water Quality Section
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled("💧 Water Quality", Style::default().fg(Color::Cyan))));
     
-    let water = &app.save_data.water;
+    let water = &app.sim.tank().water;
     
     // Purity
     let purity_color = if water.purity > 80.0 { Color::Green } 
@@ -381,37 +751,100 @@ fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
     lines.push(Line::from(draw_stat_bar(water.purity, 10)));
     
     // Temp & pH
+    // Red when any live fish is unhappy with the pH
+    let ph_ok = app.sim.tank().fish.iter().filter(|f| f.alive).all(|f| {
+        let (min, max) = f.ph_tolerance();
+        (min..=max).contains(&water.ph)
+    });
+    let ph_color = if ph_ok { Color::Green } else { Color::Red };
     lines.push(Line::from(vec![
         Span::raw(format!("Temp: {:.1}°C  ", water.temperature)),
-        Span::raw(format!("pH: {:.1}", water.ph)),
+        Span::raw("pH: "),
+        Span::styled(format!("{:.1}", water.ph), Style::default().fg(ph_color)),
+    ]));
+    lines.push(Line::from(format!("Substrate: {}", app.sim.tank().substrate.name())));
+    let light = app.sim.tank().light;
+    lines.push(Line::from(format!(
+        "💡 Light: {:02}:00-{:02}:00 at {:.0}%",
+        light.on_hour,
+        light.off_hour,
+        light.intensity * 100.0
+    )));
+    let algae = app.sim.tank().algae_level;
+    let algae_color = if algae > 50.0 { Color::Red } else if algae > 25.0 { Color::Yellow } else { Color::Green };
+    lines.push(Line::from(vec![
+        Span::raw("Algae: "),
+        Span::styled(format!("{:.0}%", algae), Style::default().fg(algae_color)),
     ]));
 
+    // Nitrogen cycle
+    let level_color = |value: f32, warn: f32, danger: f32| {
+        if value >= danger { Color::Red } else if value >= warn { Color::Yellow } else { Color::Green }
+    };
+    lines.push(Line::from(vec![
+        Span::raw("NH3 "),
+        Span::styled(format!("{:.2} ", water.ammonia), Style::default().fg(level_color(water.ammonia, 0.1, 0.25))),
+        Span::raw("NO2 "),
+        Span::styled(format!("{:.2} ", water.nitrite), Style::default().fg(level_color(water.nitrite, 0.25, 0.5))),
+        Span::raw("NO3 "),
+        Span::styled(format!("{:.0}", water.nitrate), Style::default().fg(level_color(water.nitrate, 20.0, 40.0))),
+    ]));
+    let oxygen_color = if water.oxygen < 4.0 { Color::Red } else if water.oxygen < 5.5 { Color::Yellow } else { Color::Green };
+    lines.push(Line::from(vec![
+        Span::raw("O2: "),
+        Span::styled(format!("{:.1} mg/L", water.oxygen), Style::default().fg(oxygen_color)),
+        Span::styled(if water.oxygen < 4.0 { " Gasping!" } else { "" }, Style::default().fg(Color::Red)),
+    ]));
+    let cycle_text = if water.bacteria >= 80.0 { "Cycled" } else { "Cycling..." };
+    lines.push(Line::from(vec![
+        Span::raw(format!("Bacteria: {:.0}% ", water.bacteria)),
+        Span::styled(cycle_text, Style::default().fg(if water.bacteria >= 80.0 { Color::Green } else { Color::Yellow })),
+    ]));
+    if let Some(treatment) = &app.sim.tank().treatment {
+        lines.push(Line::from(Span::styled(
+            format!("💊 {} ({:.0}h left)", treatment.medication.name(), treatment.hours_left),
+            Style::default().fg(Color::Magenta),
+        )));
+    }
+
     // Equipment Section
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled("⚙️ Equipment", Style::default().fg(Color::Cyan))));
     
-    let eq = &app.save_data.equipment;
-    let mut eq_spans = Vec::new();
-    
-    if eq.has_filter {
-        eq_spans.push(Span::styled("⚡Filter ", Style::default().fg(Color::Green)));
-    } else {
-        eq_spans.push(Span::styled("Filter ", Style::default().fg(Color::DarkGray)));
+    let eq = &app.sim.tank().equipment;
+    if eq.items.is_empty() {
+        lines.push(Line::from(Span::styled("Nothing installed", Style::default().fg(Color::DarkGray))));
     }
-    
-    if eq.has_heater {
-        eq_spans.push(Span::styled("🌡️Heater ", Style::default().fg(Color::Red)));
-    } else {
-        eq_spans.push(Span::styled("Heater ", Style::default().fg(Color::DarkGray)));
+    for item in &eq.items {
+        let (note, color) = if item.needs_service() { (" needs cleaning", Color::Yellow) } else { ("", Color::Green) };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{}{} ", item.model.kind().icon(), item.model.name()), Style::default().fg(color)),
+            Span::raw(format!("{:.0}% worn", item.wear)),
+            Span::styled(note, Style::default().fg(Color::Yellow)),
+        ]));
     }
-    
-    if eq.has_plants {
-        eq_spans.push(Span::styled("🌿Plants", Style::default().fg(Color::Green)));
-    } else {
-        eq_spans.push(Span::styled("Plants", Style::default().fg(Color::DarkGray)));
+
+    // Incidents still waiting for the player
+    let incidents = &app.sim.tank().incidents;
+    if !incidents.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("🚨 Incidents ([H] to deal with)", Style::default().fg(Color::Red))));
+        for incident in incidents {
+            let text = match incident {
+                Incident::JumpedOut { fish, hours_left } => {
+                    format!("🐟 {} is on the floor! ({:.0} min left)", fish.name, hours_left * 60.0)
+                }
+                Incident::Heatwave { hours_left, cooled: true } => {
+                    format!("{} (fan running, {:.0}h left)", incident.kind().alert(), hours_left)
+                }
+                Incident::PowerOutage { hours_left } | Incident::Heatwave { hours_left, .. } => {
+                    format!("{} ({:.0}h left)", incident.kind().alert(), hours_left)
+                }
+                _ => incident.kind().alert().to_string(),
+            };
+            lines.push(Line::from(Span::styled(text, Style::default().fg(Color::Red))));
+        }
     }
-    
-    lines.push(Line::from(eq_spans));
 
     // Notifications
     if !app.notifications.is_empty() {
@@ -433,18 +866,27 @@ fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_controls(frame: &mut Frame, app: &App, area: Rect) {
-    let fish_count = app.save_data.fish.iter().filter(|f| f.alive).count();
+    let fish_count = app.sim.tank().fish.iter().filter(|f| f.alive).count();
     
-    let freeze_text = if app.save_data.is_frozen { "[Z]Unfreeze" } else { "[Z]Freeze" };
+    let freeze_text = if app.sim.save_data.is_frozen { "[Z]Unfreeze" } else { "[Z]Freeze" };
+    let help_text = app.sim.tank().incidents.iter().find(|i| i.needs_response()).map_or(String::new(), |i| format!("[H] {} ", i.kind().response()));
     
-    let controls_text = if fish_count > 0 {
-        if app.save_data.fish.len() < 10 {
-            format!("v0.9.4 [F]eed [N]ew [W]ater [E]quip [S]crub [T]heme [D]ecorate [X]Remove {}", freeze_text)
-        } else {
-            format!("v0.9.4 [F]eed [W]ater [E]quip [S]crub [T]heme [D]ecorate [X]Remove {}", freeze_text)
-        }
+    let controls_text = if app.away_report.is_some() {
+        "v0.9.4 [↑/↓] Scroll [Enter] Dismiss report".to_string()
+    } else if app.family_tree.is_some() {
+        "v0.9.4 [←/→] Other fish [L] Close family tree".to_string()
+    } else if app.light_menu.is_some() {
+        "v0.9.4 [↑/↓] Select [←/→] Adjust [O] Close".to_string()
+    } else if app.equipment_menu.is_some() {
+        "v0.9.4 [↑/↓] Select [Enter] Install/Remove [←/→] Position [C]lean [X] Throw out spare [E] Close".to_string()
+    } else if let Some((id, to)) = app.moving_fish {
+        let name = app.sim.tank().fish.iter().find(|f| f.id == id).map_or("", |f| f.name.as_str());
+        let tank = app.sim.save_data.tanks.get(to).map_or("", |t| t.name.as_str());
+        format!("v0.9.4 [←/→] Fish [Tab] Tank [Enter] Move {} to {} [Esc] Cancel", name, tank)
+    } else if fish_count > 0 {
+        format!("v0.9.4 {}[F]eed {} [Shift+F]Food [N]ew [W]ater [E]quip [O]Light [G]round [A]quarium [S]crub [M]edicate [L]ineage [T]heme [D]ecorate [X]Remove [Tab]Tank [+]New tank [V]Move {}", help_text, app.selected_food.name(), freeze_text)
     } else {
-        format!("v0.9.4 [N]ew [D]ecorate [X]Remove [R]estart [Q]uit {}", freeze_text)
+        format!("v0.9.4 {}[N]ew [A]quarium [D]ecorate [X]Remove [Tab]Tank [+]New tank [R]estart [Q]uit {}", help_text, freeze_text)
     };
 
     let block = Block::default()
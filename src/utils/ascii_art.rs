@@ -1,124 +1,56 @@
-use crate::models::{Fish, Species, GrowthStage};
+use fishtank::models::{Disease, Fish, GrowthStage};
 
 /// ASCII fish sprites - simple and compact like asciiquarium
 pub struct FishSprite;
 
 impl FishSprite {
-    /// Get fish sprite based on species and growth stage
-    /// Returns a slice of strings (lines)
-    pub fn from_fish(fish: &Fish, _frame: u8) -> &[&'static str] {
+    /// Get fish sprite from its species definition and growth stage, with
+    /// the tail its fin genes give it
+    pub fn from_fish(fish: &Fish, _frame: u8) -> Vec<String> {
+        let sprite = fish.species.def().sprite(fish.stage);
         let facing_right = fish.velocity.0 >= 0.0;
-        
-        match fish.stage {
-            GrowthStage::Fry => {
-                 if facing_right { &[".>"] } else { &["<."] }
-            },
-            GrowthStage::Juvenile => {
-                match fish.species {
-                    Species::Goldfish => if facing_right { &["><>"] } else { &["<><"] },
-                    Species::Betta => if facing_right { &[">∫>"] } else { &["<∫<"] },
-                    Species::Guppy => if facing_right { &[">°>"] } else { &["<°<"] },
-                    Species::NeonTetra => if facing_right { &[">->"] } else { &["<-<"] },
-                    Species::Angelfish => if facing_right { &[">^>"] } else { &["<^<"] },
-                    Species::Clownfish => if facing_right { &[">|>"] } else { &["<|<"] },
-                    Species::Koi => if facing_right { &[">S>"] } else { &["<S<"] },
-                    Species::Pufferfish => if facing_right { &[">()"] } else { &["()<"] },
+        let mut lines = if facing_right { sprite.right.clone() } else { sprite.left.clone() };
+
+        // Fry haven't grown their fins yet
+        let (tail_right, tail_left) = fish.genome.fin_shape().tail();
+        if fish.stage != GrowthStage::Fry && !tail_right.is_empty() {
+            let last = lines.len() - 1;
+            for (i, line) in lines.iter_mut().enumerate() {
+                if facing_right {
+                    // Tail on the left of the body line, keep the rest aligned
+                    let pad = if i == last { tail_right.to_string() } else { " ".repeat(tail_right.chars().count()) };
+                    line.insert_str(0, &pad);
+                } else if i == last {
+                    line.push_str(tail_left);
+                }
+            }
+        }
+
+        match fish.infection.map(|i| i.disease) {
+            // White spots speckle the body
+            Some(Disease::Ich) => {
+                for line in lines.iter_mut() {
+                    *line = line
+                        .chars()
+                        .enumerate()
+                        .map(|(i, c)| if i % 3 == 1 && !c.is_whitespace() { '∙' } else { c })
+                        .collect();
                 }
-            },
-            GrowthStage::Adult => {
-                // Multi-line sprites for adults
-                match fish.species {
-                    Species::Goldfish => if facing_right { 
-                        &[
-                            "  ,·´", 
-                            "><(((º>" 
-                        ] 
-                    } else { 
-                        &[
-                             "  `·.",
-                             "<º)))><" 
-                        ] 
-                    },
-                    Species::Betta => if facing_right { 
-                        &[
-                            "  /\\", 
-                            "«(ll)>>" 
-                        ] 
-                    } else { 
-                        &[
-                             "  /\\", 
-                             "<<(ll)»" 
-                        ] 
-                    },
-                    Species::Guppy => if facing_right { 
-                        &[
-                            " ¸.·´", 
-                            "><>°>" 
-                        ] 
-                    } else { 
-                        &[
-                             " `·.¸", 
-                             "<°<><" 
-                        ] 
-                    },
-                    Species::NeonTetra => if facing_right { 
-                        &[
-                            "  __", 
-                            "><===>" 
-                        ] 
-                    } else { 
-                        &[
-                             "  __", 
-                             "<===><" 
-                        ] 
-                    },
-                    Species::Angelfish => if facing_right { 
-                        &[
-                            "   />", 
-                            " >( ))>" 
-                        ] 
-                    } else { 
-                        &[
-                             " <\\", 
-                             "<(( )<" 
-                        ] 
-                    },
-                    Species::Clownfish => if facing_right { 
-                        &[
-                            "   ,·", 
-                            "><|(|)?>" 
-                        ] 
-                    } else { 
-                        &[
-                             "  ·,", 
-                             "<?(|)|><" 
-                        ] 
-                    },
-                    Species::Koi => if facing_right { 
-                        &[
-                            "   _,,", 
-                            "><((('>" 
-                        ] 
-                    } else { 
-                        &[
-                             "   ,,_", 
-                             "<')))><" 
-                        ] 
-                    },
-                    Species::Pufferfish => if facing_right { 
-                        &[
-                            "  ..", 
-                            ">(())>" 
-                        ] 
-                    } else { 
-                        &[
-                             "  ..", 
-                             "<(( ))<" 
-                        ] 
-                    },
+            }
+            // The tail frays away
+            Some(Disease::FinRot) => {
+                let last = lines.len() - 1;
+                let tail = &mut lines[last];
+                let mut fins = tail.char_indices().filter(|(_, c)| !c.is_whitespace());
+                let end = if facing_right { fins.next() } else { fins.next_back() };
+                if let Some((i, c)) = end {
+                    tail.replace_range(i..i + c.len_utf8(), "‚");
                 }
             }
+            // Swim bladder shows in how the fish floats, not its shape
+            Some(Disease::SwimBladder) | None => {}
         }
+        lines
     }
 }
 
@@ -126,10 +58,12 @@ impl FishSprite {
 pub struct TankElements;
 
 impl TankElements {
+    #[allow(dead_code)]
     pub fn water_line(width: usize) -> String {
         "≈".repeat(width)
     }
 
+    #[allow(dead_code)]
     pub fn substrate_line(width: usize) -> String {
         "▓".repeat(width)
     }
@@ -1,3 +1,3 @@
 pub mod ascii_art;
 
-pub use ascii_art::{FishSprite, TankElements, draw_stat_bar, stat_color_indicator};
+pub use ascii_art::{FishSprite, draw_stat_bar, stat_color_indicator};